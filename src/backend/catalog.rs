@@ -0,0 +1,173 @@
+use super::error::LoadError;
+use super::omm::Omm;
+use super::tle::{TLE, ValidationMode};
+
+use std::fmt;
+use std::fs;
+use std::io::BufRead;
+use std::path::Path;
+
+// Where an element set starts in a catalog, counting from 1
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Position {
+    /// Line of a TLE file
+    Line(usize),
+
+    /// Message of an OMM file
+    Message(usize),
+}
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Position::Line(line) => write!(f, "line {}", line),
+            Position::Message(message) => write!(f, "message {}", message),
+        }
+    }
+}
+
+// Element set of a catalog that could not be parsed
+#[derive(Debug)]
+pub struct CatalogError {
+    pub position: Position,
+    pub error: LoadError,
+}
+
+impl fmt::Display for CatalogError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.position, self.error)
+    }
+}
+
+// Every element set of a TLE file, like the Celestrak group files (weather.txt, active.txt, ...), or of an OMM file
+pub struct Catalog {
+    entries: Vec<TLE>,
+    errors: Vec<CatalogError>
+}
+
+impl Catalog
+{
+    // Extensions of the files read as OMM, any other file is read as TLEs
+    pub const OMM_EXTENSIONS: [&'static str; 4] = ["xml", "kvn", "json", "csv"];
+
+    pub fn new(file_path: &str, mode: ValidationMode) -> Result<Self, LoadError>
+    {
+        let contents = fs::read_to_string(file_path)?;
+
+        let is_omm = Path::new(file_path)
+            .extension()
+            .is_some_and(|ext| Self::OMM_EXTENSIONS.iter().any(|omm| ext == *omm));
+
+        if is_omm {
+            return Self::parse_omm(&contents);
+        }
+
+        Ok(Self::parse_with_mode(&contents, mode))
+    }
+
+    // Every element set until the end of the reader
+    pub fn from_reader<R: BufRead>(mut reader: R, mode: ValidationMode) -> Result<Self, LoadError>
+    {
+        let mut contents = String::new();
+
+        reader.read_to_string(&mut contents)?;
+
+        Ok(Self::parse_with_mode(&contents, mode))
+    }
+
+    // Every message of an OMM file, in any of its formats
+    pub fn parse_omm(contents: &str) -> Result<Self, LoadError>
+    {
+        let mut entries = Vec::new();
+        let mut errors = Vec::new();
+
+        for (i, omm) in Omm::parse(contents)?.iter().enumerate() {
+            match omm.to_tle() {
+                Ok(tle) => entries.push(tle),
+                Err(error) => errors.push(CatalogError { position: Position::Message(i + 1), error: error.into() }),
+            }
+        }
+
+        Ok(Catalog {
+            entries: entries,
+            errors: errors
+        })
+    }
+
+    // Parses every element set, the ones that fail are kept as errors instead of aborting
+    pub fn parse(contents: &str) -> Self
+    {
+        Self::parse_with_mode(contents, ValidationMode::Strict)
+    }
+
+    pub fn parse_with_mode(contents: &str, mode: ValidationMode) -> Self
+    {
+        let lines: Vec<(usize, &str)> = contents
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(i, line)| (i + 1, line))
+            .collect();
+
+        let mut entries = Vec::new();
+        let mut errors = Vec::new();
+
+        let mut start = 0;
+
+        while start < lines.len() {
+            // An element set is an optional name line followed by lines 1 and 2
+            let mut end = start;
+
+            if !Self::is_element_line(lines[end].1) {
+                end += 1;
+            }
+
+            if end < lines.len() && lines[end].1.starts_with("1 ") {
+                end += 1;
+            }
+
+            if end < lines.len() && lines[end].1.starts_with("2 ") {
+                end += 1;
+            }
+
+            let element_set: Vec<&str> = lines[start..end].iter().map(|(_, line)| *line).collect();
+
+            match TLE::parse_with_mode(&element_set.join("\n"), mode) {
+                Ok(tle) => entries.push(tle),
+                Err(error) => errors.push(CatalogError { position: Position::Line(lines[start].0), error: error.into() }),
+            }
+
+            start = end;
+        }
+
+        Catalog {
+            entries: entries,
+            errors: errors
+        }
+    }
+
+    // Whether the contents have a line 1 followed by a line 2, to tell TLE files from other text files
+    pub fn has_element_sets(contents: &str) -> bool
+    {
+        let lines: Vec<&str> = contents.lines().filter(|line| !line.trim().is_empty()).collect();
+
+        return lines.windows(2).any(|pair| pair[0].starts_with("1 ") && pair[1].starts_with("2 "));
+    }
+
+    fn is_element_line(line: &str) -> bool
+    {
+        return line.starts_with("1 ") || line.starts_with("2 ");
+    }
+
+    // Getters
+
+    pub fn get_entries(&self) -> &Vec<TLE>
+    {
+        return &self.entries;
+    }
+
+    pub fn get_errors(&self) -> &Vec<CatalogError>
+    {
+        return &self.errors;
+    }
+}
@@ -0,0 +1,287 @@
+use super::ellipsoid::Ellipsoid;
+use super::matrix::Matrix3;
+use super::vector::PositionVector;
+
+use core::f64::consts::{FRAC_PI_2, PI};
+
+// Distance in km to the vertical of an observer below which a target is taken to be at its zenith
+const ZENITH_DISTANCE: f64 = 1.0e-9;
+
+/*
+ * Coordinates tagged with the frame they are measured in, so only the conversions between valid pairs exist:
+ *
+ *  Teme ----> Ecef <----> Geodetic
+ *   |          |
+ *   v          v
+ *  Gcrf       Enu ----> LookAngles
+ *
+ * The TEME to ECEF and GCRF conversions need the orientation of the earth and are in the frames module.
+*/
+
+// Position (km) and velocity (km/s) in the True Equator Mean Equinox frame, the output of SGP4
+#[derive(Clone, Debug)]
+pub struct Teme {
+    pub position: PositionVector,
+    pub velocity: PositionVector,
+}
+
+impl Teme {
+
+    pub fn new(position: PositionVector, velocity: PositionVector) -> Self
+    {
+        Self {
+            position,
+            velocity
+        }
+    }
+
+    pub fn get_position(&self) -> &PositionVector
+    {
+        &self.position
+    }
+
+    pub fn get_velocity(&self) -> &PositionVector
+    {
+        &self.velocity
+    }
+}
+
+// Position (km) and velocity (km/s) in the Geocentric Celestial Reference Frame
+#[derive(Clone, Debug)]
+pub struct Gcrf {
+    pub position: PositionVector,
+    pub velocity: PositionVector,
+}
+
+impl Gcrf {
+
+    pub fn new(position: PositionVector, velocity: PositionVector) -> Self
+    {
+        Self {
+            position,
+            velocity
+        }
+    }
+
+    pub fn get_position(&self) -> &PositionVector
+    {
+        &self.position
+    }
+
+    pub fn get_velocity(&self) -> &PositionVector
+    {
+        &self.velocity
+    }
+}
+
+// Position (km) and velocity (km/s) in an earth fixed frame (ITRF or PEF), the velocity is relative to the earth
+#[derive(Clone, Debug)]
+pub struct Ecef {
+    pub position: PositionVector,
+    pub velocity: PositionVector,
+}
+
+impl Ecef {
+
+    pub fn new(position: PositionVector, velocity: PositionVector) -> Self
+    {
+        Self {
+            position,
+            velocity
+        }
+    }
+
+    pub fn get_position(&self) -> &PositionVector
+    {
+        &self.position
+    }
+
+    pub fn get_velocity(&self) -> &PositionVector
+    {
+        &self.velocity
+    }
+
+    // Exact conversion of Vermeille (2002), valid anywhere but close to the center of the earth
+    pub fn to_geodetic(&self, ellipsoid: &Ellipsoid) -> Geodetic
+    {
+        // H. Vermeille, "Direct transformation from geocentric coordinates to geodetic coordinates", Journal of Geodesy 76 (2002)
+
+        let a = ellipsoid.semi_major_axis();
+        let e2 = ellipsoid.eccentricity_squared();
+        let e4 = e2 * e2;
+
+        let (x, y, z) = (self.position.x, self.position.y, self.position.z);
+        let distance_to_axis = (x*x + y*y).sqrt();
+
+        let p = (x*x + y*y) / (a*a);
+        let q = (1.0 - e2) * z*z / (a*a);
+        let r = (p + q - e4) / 6.0;
+        let s = e4 * p * q / (4.0 * r.powi(3));
+        let t = (1.0 + s + (s * (2.0 + s)).sqrt()).cbrt();
+        let u = r * (1.0 + t + 1.0 / t);
+        let v = (u*u + e4*q).sqrt();
+        let w = e2 * (u + v - q) / (2.0 * v);
+        let k = (u + v + w*w).sqrt() - w;
+        let d = k * distance_to_axis / (k + e2);
+
+        let lat = 2.0 * z.atan2(d + (d*d + z*z).sqrt());
+        let lon = y.atan2(x);
+        let h = (k + e2 - 1.0) / k * (d*d + z*z).sqrt();
+
+        Geodetic::new(lat, lon, h)
+    }
+
+    // Position and velocity relative to the observer in its local east, north, up axes, over the ellipsoid of the observer
+    pub fn to_enu(&self, observer: &Geodetic, ellipsoid: &Ellipsoid) -> Enu
+    {
+        // https://gssc.esa.int/navipedia/index.php/Transformations_between_ECEF_and_ENU_coordinates
+
+        let observer_ecef = observer.to_ecef(ellipsoid);
+        let rotation = Self::enu_rotation(observer);
+
+        Enu::new(
+            rotation * (self.position - observer_ecef.position),
+            rotation * (self.velocity - observer_ecef.velocity)
+        )
+    }
+
+    // From the earth fixed axes to the east, north, up axes of the observer
+    fn enu_rotation(observer: &Geodetic) -> Matrix3
+    {
+        Matrix3::rotation_x(FRAC_PI_2 - observer.latitude) * Matrix3::rotation_z(FRAC_PI_2 + observer.longitude)
+    }
+}
+
+// Latitude and longitude in rad and altitude in km over a reference ellipsoid
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Geodetic {
+    pub latitude: f64,
+    pub longitude: f64,
+    pub altitude: f64,
+}
+
+impl Geodetic {
+
+    pub fn new(latitude: f64, longitude: f64, altitude: f64) -> Self
+    {
+        Self {
+            latitude,
+            longitude,
+            altitude
+        }
+    }
+
+    pub fn from_degrees(latitude: f64, longitude: f64, altitude: f64) -> Self
+    {
+        Self::new(latitude.to_radians(), longitude.to_radians(), altitude)
+    }
+
+    // Earth fixed position of the point, at rest with the earth
+    pub fn to_ecef(&self, ellipsoid: &Ellipsoid) -> Ecef
+    {
+        // https://en.wikipedia.org/wiki/Geographic_coordinate_conversion
+
+        let (lat, lon, h) = (self.latitude, self.longitude, self.altitude);
+
+        let n = ellipsoid.prime_vertical_radius(lat);
+
+        let position = PositionVector::new(
+            (n + h)*lat.cos()*lon.cos(),
+            (n + h)*lat.cos()*lon.sin(),
+            (n*(1.0 - ellipsoid.eccentricity_squared()) + h)*lat.sin()
+        );
+
+        Ecef::new(position, PositionVector::new(0.0, 0.0, 0.0))
+    }
+
+    pub fn get_latitude_degrees(&self) -> f64
+    {
+        return self.latitude.to_degrees();
+    }
+
+    pub fn get_longitude_degrees(&self) -> f64
+    {
+        return self.longitude.to_degrees();
+    }
+}
+
+// Position (km) and velocity (km/s) relative to an observer, along its local east, north and up axes (x, y, z)
+#[derive(Clone, Debug)]
+pub struct Enu {
+    pub position: PositionVector,
+    pub velocity: PositionVector,
+}
+
+impl Enu {
+
+    pub fn new(position: PositionVector, velocity: PositionVector) -> Self
+    {
+        Self {
+            position,
+            velocity
+        }
+    }
+
+    pub fn get_position(&self) -> &PositionVector
+    {
+        &self.position
+    }
+
+    pub fn get_velocity(&self) -> &PositionVector
+    {
+        &self.velocity
+    }
+
+    // Vallado, "Fundamentals of Astrodynamics and Applications", algorithm 27 (RAZEL)
+    pub fn to_look_angles(&self) -> LookAngles
+    {
+        let (east, north, up) = (self.position.x, self.position.y, self.position.z);
+        let (east_rate, north_rate, up_rate) = (self.velocity.x, self.velocity.y, self.velocity.z);
+
+        let range = self.position.norm();
+        let range_rate = self.position.dot(&self.velocity) / range;
+
+        // Squared distance to the vertical of the observer, the azimuth is undefined over it
+        let horizontal = east*east + north*north;
+
+        let elevation = (up / range).asin();
+
+        // Straight over the observer the rates of the angles have no value (a division by zero)
+        let overhead = horizontal.sqrt() < ZENITH_DISTANCE;
+
+        LookAngles {
+            // Clockwise from the north, in [0, 2π)
+            azimuth: east.atan2(north).rem_euclid(2.0 * PI),
+            elevation: elevation,
+            range: range,
+            azimuth_rate: (!overhead).then(|| (east_rate*north - north_rate*east) / horizontal),
+            elevation_rate: (!overhead).then(|| (up_rate - range_rate*elevation.sin()) / horizontal.sqrt()),
+            range_rate: range_rate
+        }
+    }
+}
+
+// Where the observer has to look: azimuth and elevation in rad and range in km, with their rates per second.
+// The rates of the angles are None when the target is at the zenith
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LookAngles {
+    pub azimuth: f64,
+    pub elevation: f64,
+    pub range: f64,
+    pub azimuth_rate: Option<f64>,
+    pub elevation_rate: Option<f64>,
+    pub range_rate: f64,
+}
+
+impl LookAngles {
+
+    pub fn get_azimuth_degrees(&self) -> f64
+    {
+        return self.azimuth.to_degrees();
+    }
+
+    pub fn get_elevation_degrees(&self) -> f64
+    {
+        return self.elevation.to_degrees();
+    }
+}
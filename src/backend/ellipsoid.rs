@@ -0,0 +1,73 @@
+// Reference ellipsoids of the earth (datums), the geodetic coordinates are measured over one of them
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Ellipsoid {
+    #[default]
+    WGS84,
+    WGS72,
+    GRS80,
+    Spherical,
+}
+
+impl Ellipsoid {
+    pub const ALL: [Ellipsoid; 4] = [Ellipsoid::WGS84, Ellipsoid::WGS72, Ellipsoid::GRS80, Ellipsoid::Spherical];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Ellipsoid::WGS84 => "WGS-84",
+            Ellipsoid::WGS72 => "WGS-72",
+            Ellipsoid::GRS80 => "GRS-80",
+            Ellipsoid::Spherical => "Spherical",
+        }
+    }
+
+    // Ellipsoid of the given name, ignoring case and dashes ("wgs72", "WGS-72", ...)
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.replace('-', "").to_uppercase();
+
+        Self::ALL.into_iter().find(|ellipsoid| ellipsoid.name().replace('-', "").to_uppercase() == name)
+    }
+
+    // Equatorial radius in km
+    pub fn semi_major_axis(&self) -> f64
+    {
+        match self {
+            Ellipsoid::WGS84 => 6378.137,
+            Ellipsoid::WGS72 => 6378.135,
+            Ellipsoid::GRS80 => 6378.137,
+            // Mean radius of the earth (IUGG)
+            Ellipsoid::Spherical => 6371.0088,
+        }
+    }
+
+    // (a - b) / a
+    pub fn flattening(&self) -> f64
+    {
+        match self {
+            Ellipsoid::WGS84 => 1.0 / 298.257223563,
+            Ellipsoid::WGS72 => 1.0 / 298.26,
+            Ellipsoid::GRS80 => 1.0 / 298.257222101,
+            Ellipsoid::Spherical => 0.0,
+        }
+    }
+
+    // Polar radius in km
+    pub fn semi_minor_axis(&self) -> f64
+    {
+        return self.semi_major_axis() * (1.0 - self.flattening());
+    }
+
+    // Square of the first eccentricity
+    pub fn eccentricity_squared(&self) -> f64
+    {
+        return self.flattening() * (2.0 - self.flattening());
+    }
+
+    // Radius of curvature in the prime vertical at the given geodetic latitude
+    pub fn prime_vertical_radius(&self, latitude: f64) -> f64
+    {
+        let temp_1 = (latitude.sin()).powi(2);
+        let temp_2 = (1.0 - self.eccentricity_squared()*temp_1).sqrt();
+
+        self.semi_major_axis() / temp_2
+    }
+}
@@ -0,0 +1,51 @@
+use super::omm::OmmError;
+use super::tle::TleError;
+
+use std::fmt;
+use std::io;
+
+// Failure to load element sets from a file or reader
+#[derive(Debug)]
+pub enum LoadError {
+    Io(io::Error),
+    Tle(TleError),
+    Omm(OmmError),
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LoadError::Io(error) => write!(f, "I/O error: {}", error),
+            LoadError::Tle(error) => write!(f, "{}", error),
+            LoadError::Omm(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for LoadError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            LoadError::Io(error) => Some(error),
+            LoadError::Tle(error) => Some(error),
+            LoadError::Omm(error) => Some(error),
+        }
+    }
+}
+
+impl From<io::Error> for LoadError {
+    fn from(error: io::Error) -> Self {
+        LoadError::Io(error)
+    }
+}
+
+impl From<TleError> for LoadError {
+    fn from(error: TleError) -> Self {
+        LoadError::Tle(error)
+    }
+}
+
+impl From<OmmError> for LoadError {
+    fn from(error: OmmError) -> Self {
+        LoadError::Omm(error)
+    }
+}
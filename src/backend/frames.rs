@@ -0,0 +1,170 @@
+use super::coordinates::{Ecef, Gcrf, Teme};
+use super::time::{self, JulianDate};
+use super::matrix::Matrix3;
+use super::timescale::{TimeScale, TimeScales};
+use super::vector::PositionVector;
+
+use chrono::{DateTime, Utc};
+
+// Rotation rate of the earth in rad/s, the one of the IERS conventions without the length of day
+pub const EARTH_ROTATION_RATE: f64 = 7.292115146706979e-5;
+
+const ARCSECONDS_TO_RAD: f64 = core::f64::consts::PI / (180.0 * 3600.0);
+
+// Largest terms of the IAU-1980 nutation (above 0.005 arcsec) as multipliers of D, M, M', F, Ω and the
+// coefficients of Δψ and Δε in 0.0001 arcsec (constant, per Julian century)
+const NUTATION_TERMS: [([f64; 5], [f64; 4]); 18] = [
+    ([0.0, 0.0, 0.0, 0.0, 1.0], [-171996.0, -174.2, 92025.0, 8.9]),
+    ([-2.0, 0.0, 0.0, 2.0, 2.0], [-13187.0, -1.6, 5736.0, -3.1]),
+    ([0.0, 0.0, 0.0, 2.0, 2.0], [-2274.0, -0.2, 977.0, -0.5]),
+    ([0.0, 0.0, 0.0, 0.0, 2.0], [2062.0, 0.2, -895.0, 0.5]),
+    ([0.0, 1.0, 0.0, 0.0, 0.0], [1426.0, -3.4, 54.0, -0.1]),
+    ([0.0, 0.0, 1.0, 0.0, 0.0], [712.0, 0.1, -7.0, 0.0]),
+    ([-2.0, 1.0, 0.0, 2.0, 2.0], [-517.0, 1.2, 224.0, -0.6]),
+    ([0.0, 0.0, 0.0, 2.0, 1.0], [-386.0, -0.4, 200.0, 0.0]),
+    ([0.0, 0.0, 1.0, 2.0, 2.0], [-301.0, 0.0, 129.0, -0.1]),
+    ([-2.0, -1.0, 0.0, 2.0, 2.0], [217.0, -0.5, -95.0, 0.3]),
+    ([-2.0, 0.0, 1.0, 0.0, 0.0], [-158.0, 0.0, 0.0, 0.0]),
+    ([-2.0, 0.0, 0.0, 2.0, 1.0], [129.0, 0.1, -70.0, 0.0]),
+    ([0.0, 0.0, -1.0, 2.0, 2.0], [123.0, 0.0, -53.0, 0.0]),
+    ([2.0, 0.0, 0.0, 0.0, 0.0], [63.0, 0.0, 0.0, 0.0]),
+    ([0.0, 0.0, 1.0, 0.0, 1.0], [63.0, 0.1, -33.0, 0.0]),
+    ([2.0, 0.0, -1.0, 2.0, 2.0], [-59.0, 0.0, 26.0, 0.0]),
+    ([0.0, 0.0, -1.0, 0.0, 1.0], [-58.0, -0.1, 32.0, 0.0]),
+    ([0.0, 0.0, 1.0, 2.0, 1.0], [-51.0, 0.0, 27.0, 0.0]),
+];
+
+// TEME to the pseudo earth fixed frame, a rotation by the GMST (IAU-82) of the UT1 instant
+pub fn teme_to_pef(state: &Teme, ut1: &JulianDate) -> Ecef
+{
+    let rotation = Matrix3::rotation_z(time::gmst_iau82(ut1));
+
+    let position = rotation * state.position;
+
+    // The frame rotates with the earth, ω x r is taken out of the velocity
+    let omega = PositionVector::new(0.0, 0.0, EARTH_ROTATION_RATE);
+    let velocity = rotation * state.velocity - omega.cross(&position);
+
+    Ecef::new(position, velocity)
+}
+
+// Pseudo earth fixed frame to ITRF, moving the pole by the polar motion (x, y) in rad
+pub fn pef_to_itrf(state: &Ecef, polar_motion: (f64, f64)) -> Ecef
+{
+    let (x_pole, y_pole) = polar_motion;
+
+    let rotation = Matrix3::rotation_y(-x_pole) * Matrix3::rotation_x(-y_pole);
+
+    Ecef::new(rotation * state.position, rotation * state.velocity)
+}
+
+// TEME to the GCRF (J2000, without the frame bias) through the true and mean of date frames, TT for the precession and nutation
+pub fn teme_to_gcrf(state: &Teme, tt: &JulianDate) -> Gcrf
+{
+    let t = tt.centuries_since_j2000();
+
+    let (delta_psi, delta_epsilon, mean_epsilon) = nutation(t);
+
+    // Precession (IAU-1976)
+    let zeta = (2306.2181*t + 0.30188*t*t + 0.017998*t*t*t) * ARCSECONDS_TO_RAD;
+    let theta = (2004.3109*t - 0.42665*t*t - 0.041833*t*t*t) * ARCSECONDS_TO_RAD;
+    let z = (2306.2181*t + 1.09468*t*t + 0.018203*t*t*t) * ARCSECONDS_TO_RAD;
+
+    // Equation of the equinoxes, the angle between the TEME and the true of date equinoxes
+    let equation_of_equinoxes = delta_psi * mean_epsilon.cos();
+
+    let teme_to_true_of_date = Matrix3::rotation_z(-equation_of_equinoxes);
+    let true_to_mean_of_date = Matrix3::rotation_x(-mean_epsilon) * Matrix3::rotation_z(delta_psi) * Matrix3::rotation_x(mean_epsilon + delta_epsilon);
+    let mean_of_date_to_gcrf = Matrix3::rotation_z(zeta) * Matrix3::rotation_y(-theta) * Matrix3::rotation_z(z);
+
+    let rotation = mean_of_date_to_gcrf * true_to_mean_of_date * teme_to_true_of_date;
+
+    Gcrf::new(rotation * state.position, rotation * state.velocity)
+}
+
+// Nutation in longitude, in obliquity and the mean obliquity in rad at the given TT centuries since J2000
+fn nutation(t: f64) -> (f64, f64, f64)
+{
+    // Fundamental arguments: elongation of the moon, anomalies of the sun and moon, latitude of the moon and its ascending node
+    let arguments = [
+        297.85036 + 445267.111480*t - 0.0019142*t*t + t*t*t / 189474.0,
+        357.52772 + 35999.050340*t - 0.0001603*t*t - t*t*t / 300000.0,
+        134.96298 + 477198.867398*t + 0.0086972*t*t + t*t*t / 56250.0,
+        93.27191 + 483202.017538*t - 0.0036825*t*t + t*t*t / 327270.0,
+        125.04452 - 1934.136261*t + 0.0020708*t*t + t*t*t / 450000.0,
+    ].map(|degrees: f64| degrees.to_radians());
+
+    let mut delta_psi = 0.0;
+    let mut delta_epsilon = 0.0;
+
+    for (multipliers, coefficients) in NUTATION_TERMS {
+        let argument: f64 = multipliers.iter().zip(arguments).map(|(m, a)| m * a).sum();
+
+        delta_psi += (coefficients[0] + coefficients[1]*t) * argument.sin();
+        delta_epsilon += (coefficients[2] + coefficients[3]*t) * argument.cos();
+    }
+
+    let mean_epsilon = 84381.448 - 46.8150*t - 0.00059*t*t + 0.001813*t*t*t;
+
+    (delta_psi * 1.0e-4 * ARCSECONDS_TO_RAD, delta_epsilon * 1.0e-4 * ARCSECONDS_TO_RAD, mean_epsilon * ARCSECONDS_TO_RAD)
+}
+
+// Orientation of the earth at an UTC instant, the UT1, TT and polar motion come from the time scales
+#[derive(Clone, Copy, Debug)]
+pub struct EarthOrientation {
+    ut1: JulianDate,
+    tt: JulianDate,
+    polar_motion: Option<(f64, f64)>
+}
+
+impl EarthOrientation
+{
+    pub fn new(utc: DateTime<Utc>, scales: &TimeScales) -> Self
+    {
+        let polar_motion = scales
+            .get_eop(&JulianDate::from_datetime(utc))
+            .map(|entry| (entry.x_pole, entry.y_pole));
+
+        EarthOrientation {
+            ut1: scales.convert(TimeScale::UT1, utc),
+            tt: scales.convert(TimeScale::TT, utc),
+            polar_motion: polar_motion
+        }
+    }
+
+    pub fn teme_to_pef(&self, state: &Teme) -> Ecef
+    {
+        teme_to_pef(state, &self.ut1)
+    }
+
+    // Earth fixed state, without polar motion (the PEF) when there is no EOP data for the instant
+    pub fn teme_to_itrf(&self, state: &Teme) -> Ecef
+    {
+        let pef = self.teme_to_pef(state);
+
+        match self.polar_motion {
+            Some(polar_motion) => pef_to_itrf(&pef, polar_motion),
+            None => pef,
+        }
+    }
+
+    pub fn teme_to_gcrf(&self, state: &Teme) -> Gcrf
+    {
+        teme_to_gcrf(state, &self.tt)
+    }
+
+    pub fn get_ut1(&self) -> &JulianDate
+    {
+        return &self.ut1;
+    }
+
+    pub fn get_tt(&self) -> &JulianDate
+    {
+        return &self.tt;
+    }
+
+    pub fn get_polar_motion(&self) -> Option<(f64, f64)>
+    {
+        return self.polar_motion;
+    }
+}
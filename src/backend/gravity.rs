@@ -0,0 +1,75 @@
+// Earth gravity models supported by the propagators, WGS-72 is the one used to generate the TLEs
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum GravityModel {
+    WGS72Old,
+    #[default]
+    WGS72,
+    WGS84,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct GravityConstants {
+    /// Earth gravitational parameter in km³/s²
+    pub mu: f64,
+
+    /// Equatorial radius of the earth in km
+    pub radius: f64,
+
+    /// sqrt(GM) in earth radii^1.5 / min
+    pub xke: f64,
+
+    /// Minutes in one time unit
+    pub tumin: f64,
+
+    /// Second Gravitational Zonal Harmonic of the Earth
+    pub j2: f64,
+
+    /// Third Gravitational Zonal Harmonic of the Earth
+    pub j3: f64,
+
+    /// Forth Gravitational Zonal Harmonic of the Earth
+    pub j4: f64,
+
+    pub j3oj2: f64,
+}
+
+impl GravityModel {
+    pub fn name(&self) -> &'static str {
+        match self {
+            GravityModel::WGS72Old => "WGS-72 old",
+            GravityModel::WGS72 => "WGS-72",
+            GravityModel::WGS84 => "WGS-84",
+        }
+    }
+
+    pub fn constants(&self) -> GravityConstants
+    {
+        match self {
+            // The old WGS-72 uses a truncated value of xke
+            GravityModel::WGS72Old => GravityConstants::new(398600.79964, 6378.135, Some(0.0743669161),
+                0.001082616, -0.00000253881, -0.00000165597),
+            GravityModel::WGS72 => GravityConstants::new(398600.8, 6378.135, None,
+                0.001082616, -0.00000253881, -0.00000165597),
+            GravityModel::WGS84 => GravityConstants::new(398600.5, 6378.137, None,
+                0.00108262998905, -0.00000253215306, -0.00000161098761),
+        }
+    }
+}
+
+impl GravityConstants {
+    fn new(mu: f64, radius: f64, xke: Option<f64>, j2: f64, j3: f64, j4: f64) -> Self
+    {
+        let xke = xke.unwrap_or(60.0 / (radius * radius * radius / mu).sqrt());
+
+        GravityConstants {
+            mu,
+            radius,
+            xke,
+            tumin: 1.0 / xke,
+            j2,
+            j3,
+            j4,
+            j3oj2: j3 / j2,
+        }
+    }
+}
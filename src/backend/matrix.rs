@@ -0,0 +1,91 @@
+use super::vector::PositionVector;
+
+use std::ops::Mul;
+
+// 3x3 matrix stored by rows, used for the rotations between frames
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Matrix3 {
+    pub rows: [[f64; 3]; 3],
+}
+
+impl Matrix3 {
+
+    pub fn new(rows: [[f64; 3]; 3]) -> Self
+    {
+        Self {
+            rows
+        }
+    }
+
+    pub fn identity() -> Self
+    {
+        Self::new([[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]])
+    }
+
+    /*
+     * Rotations of the axes (not of the vector) by the given angle, as the R1, R2 and R3 of Vallado.
+     * A positive angle turns the axes counterclockwise, so the vectors are seen turned clockwise.
+    */
+
+    pub fn rotation_x(angle: f64) -> Self
+    {
+        let (sin, cos) = angle.sin_cos();
+
+        Self::new([[1.0, 0.0, 0.0], [0.0, cos, sin], [0.0, -sin, cos]])
+    }
+
+    pub fn rotation_y(angle: f64) -> Self
+    {
+        let (sin, cos) = angle.sin_cos();
+
+        Self::new([[cos, 0.0, -sin], [0.0, 1.0, 0.0], [sin, 0.0, cos]])
+    }
+
+    pub fn rotation_z(angle: f64) -> Self
+    {
+        let (sin, cos) = angle.sin_cos();
+
+        Self::new([[cos, sin, 0.0], [-sin, cos, 0.0], [0.0, 0.0, 1.0]])
+    }
+
+    // The inverse for rotations
+    pub fn transpose(&self) -> Self
+    {
+        let r = &self.rows;
+
+        Self::new([
+            [r[0][0], r[1][0], r[2][0]],
+            [r[0][1], r[1][1], r[2][1]],
+            [r[0][2], r[1][2], r[2][2]],
+        ])
+    }
+}
+
+impl Mul<PositionVector> for Matrix3 {
+    type Output = PositionVector;
+
+    fn mul(self, v: PositionVector) -> PositionVector
+    {
+        let row = |i: usize| self.rows[i][0] * v.x + self.rows[i][1] * v.y + self.rows[i][2] * v.z;
+
+        PositionVector::new(row(0), row(1), row(2))
+    }
+}
+
+// Composition, the right matrix is applied first
+impl Mul for Matrix3 {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self
+    {
+        let mut rows = [[0.0; 3]; 3];
+
+        for (i, row) in rows.iter_mut().enumerate() {
+            for (j, value) in row.iter_mut().enumerate() {
+                *value = (0..3).map(|k| self.rows[i][k] * other.rows[k][j]).sum();
+            }
+        }
+
+        Self::new(rows)
+    }
+}
@@ -1,4 +1,15 @@
+pub mod catalog;
+pub mod coordinates;
+pub mod ellipsoid;
+pub mod error;
+pub mod frames;
+pub mod gravity;
+pub mod matrix;
+pub mod observer;
+pub mod omm;
 pub mod propagator;
+pub mod time;
+pub mod timescale;
 pub mod tle;
 pub mod orbit;
 pub mod satellite;
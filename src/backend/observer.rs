@@ -0,0 +1,54 @@
+use super::coordinates::{Ecef, Geodetic, LookAngles};
+use super::ellipsoid::Ellipsoid;
+
+// Place on the earth the satellites are looked at from, at rest with the earth
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Observer {
+    geodetic: Geodetic,
+    ellipsoid: Ellipsoid
+}
+
+impl Observer
+{
+    pub fn new(geodetic: Geodetic, ellipsoid: Ellipsoid) -> Self
+    {
+        Observer {
+            geodetic: geodetic,
+            ellipsoid: ellipsoid
+        }
+    }
+
+    /*
+     * Azimuth, elevation, slant range, range rate and the rates of the angles of the target.
+     * The target has to be earth fixed (ITRF or PEF), so its velocity is the one seen by the observer.
+    */
+    pub fn look_angles(&self, target: &Ecef) -> LookAngles
+    {
+        return target.to_enu(&self.geodetic, &self.ellipsoid).to_look_angles();
+    }
+
+    pub fn get_ecef(&self) -> Ecef
+    {
+        return self.geodetic.to_ecef(&self.ellipsoid);
+    }
+
+    pub fn get_geodetic(&self) -> &Geodetic
+    {
+        return &self.geodetic;
+    }
+
+    pub fn get_ellipsoid(&self) -> &Ellipsoid
+    {
+        return &self.ellipsoid;
+    }
+
+    pub fn set_geodetic(&mut self, geodetic: Geodetic)
+    {
+        self.geodetic = geodetic;
+    }
+
+    pub fn set_ellipsoid(&mut self, ellipsoid: Ellipsoid)
+    {
+        self.ellipsoid = ellipsoid;
+    }
+}
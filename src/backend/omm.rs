@@ -0,0 +1,424 @@
+use super::time::JulianDate;
+use super::tle::TLE;
+
+use chrono::{Datelike, NaiveDateTime, Timelike, Utc};
+use std::collections::HashMap;
+use std::fmt;
+
+// Formats in which Space-Track and Celestrak publish General Perturbations data
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum OmmFormat {
+    Xml,
+    Kvn,
+    Json,
+    Csv,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum OmmError {
+    /// The XML document is malformed
+    Xml(String),
+
+    /// The JSON document is malformed or isn't made of objects
+    Json(String),
+
+    /// A KVN line that isn't "KEYWORD = value"
+    Kvn { line: usize },
+
+    /// A CSV row with a different number of values than the header
+    Csv { row: usize },
+
+    /// A mandatory keyword of the element set is missing
+    MissingField(&'static str),
+
+    /// The value of the keyword can't be parsed
+    InvalidField(&'static str),
+}
+
+impl fmt::Display for OmmError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OmmError::Xml(error) => write!(f, "invalid XML: {}", error),
+            OmmError::Json(error) => write!(f, "invalid JSON: {}", error),
+            OmmError::Kvn { line } => write!(f, "KVN line {} is not KEYWORD = value", line),
+            OmmError::Csv { row } => write!(f, "CSV row {} doesn't match the header", row),
+            OmmError::MissingField(key) => write!(f, "missing keyword {}", key),
+            OmmError::InvalidField(key) => write!(f, "invalid value of {}", key),
+        }
+    }
+}
+
+impl std::error::Error for OmmError {}
+
+/*
+ * CCSDS Orbit Mean-Elements Message (CCSDS 502.0-B-3). Only the keywords of the SGP4 element sets
+ * are used, every message is kept as its keyword/value pairs and converted to the same element
+ * data as a TLE so it can be propagated with Orbit::new.
+*/
+#[derive(Clone, Debug, Default)]
+pub struct Omm {
+    fields: HashMap<String, String>
+}
+
+impl OmmFormat {
+    // Guesses the format from the first characters of the contents
+    pub fn detect(contents: &str) -> Self
+    {
+        let contents = contents.trim_start();
+
+        if contents.starts_with('<') {
+            return OmmFormat::Xml;
+        }
+
+        if contents.starts_with('[') || contents.starts_with('{') {
+            return OmmFormat::Json;
+        }
+
+        if contents.starts_with("CCSDS_OMM_VERS") || contents.starts_with("COMMENT") {
+            return OmmFormat::Kvn;
+        }
+
+        return OmmFormat::Csv;
+    }
+}
+
+impl Omm
+{
+    const UNKNOWN_NAME: &'static str = "UNKNOWN";
+
+    const EPOCH_FORMATS: [&'static str; 2] = ["%Y-%m-%dT%H:%M:%S%.f", "%Y-%jT%H:%M:%S%.f"];
+
+    // Keywords of each section of the message, in the order they are written
+    const HEADER: [&'static str; 2] = ["CREATION_DATE", "ORIGINATOR"];
+    const METADATA: [&'static str; 6] = ["OBJECT_NAME", "OBJECT_ID", "CENTER_NAME", "REF_FRAME", "TIME_SYSTEM", "MEAN_ELEMENT_THEORY"];
+    const MEAN_ELEMENTS: [&'static str; 7] = ["EPOCH", "MEAN_MOTION", "ECCENTRICITY", "INCLINATION", "RA_OF_ASC_NODE", "ARG_OF_PERICENTER", "MEAN_ANOMALY"];
+    const TLE_PARAMETERS: [&'static str; 8] = ["EPHEMERIS_TYPE", "CLASSIFICATION_TYPE", "NORAD_CAT_ID", "ELEMENT_SET_NO", "REV_AT_EPOCH", "BSTAR", "MEAN_MOTION_DOT", "MEAN_MOTION_DDOT"];
+
+    pub fn new(fields: HashMap<String, String>) -> Self
+    {
+        Omm {
+            fields: fields
+        }
+    }
+
+    // Every message of the contents, in any of the supported formats
+    pub fn parse(contents: &str) -> Result<Vec<Self>, OmmError>
+    {
+        match OmmFormat::detect(contents) {
+            OmmFormat::Xml => Self::parse_xml(contents),
+            OmmFormat::Kvn => Self::parse_kvn(contents),
+            OmmFormat::Json => Self::parse_json(contents),
+            OmmFormat::Csv => Self::parse_csv(contents),
+        }
+    }
+
+    // "KEYWORD = value [unit]" lines, a new message starts on every CCSDS_OMM_VERS
+    pub fn parse_kvn(contents: &str) -> Result<Vec<Self>, OmmError>
+    {
+        let mut messages = Vec::new();
+        let mut fields = HashMap::new();
+
+        for (i, line) in contents.lines().map(|line| line.trim()).enumerate() {
+            if line.is_empty() || line.starts_with("COMMENT") {
+                continue;
+            }
+
+            let (key, value) = line.split_once('=').ok_or(OmmError::Kvn { line: i + 1 })?;
+            let key = key.trim();
+
+            if key == "CCSDS_OMM_VERS" && !fields.is_empty() {
+                messages.push(Self::new(fields));
+                fields = HashMap::new();
+            }
+
+            // Units are optional and between brackets after the value
+            let value = value.split('[').next().unwrap_or("").trim();
+
+            fields.insert(key.to_string(), value.to_string());
+        }
+
+        if !fields.is_empty() {
+            messages.push(Self::new(fields));
+        }
+
+        Ok(messages)
+    }
+
+    // Every <omm> element of the document, the keywords are the tags of its leaf elements
+    pub fn parse_xml(contents: &str) -> Result<Vec<Self>, OmmError>
+    {
+        let document = roxmltree::Document::parse(contents).map_err(|e| OmmError::Xml(e.to_string()))?;
+
+        let messages = document
+            .descendants()
+            .filter(|node| node.has_tag_name("omm"))
+            .map(|omm| {
+                let fields = omm
+                    .descendants()
+                    .filter(|node| node.is_element() && !node.children().any(|child| child.is_element()))
+                    .map(|node| (node.tag_name().name().to_string(), node.text().unwrap_or("").trim().to_string()))
+                    .collect();
+
+                Self::new(fields)
+            })
+            .collect();
+
+        Ok(messages)
+    }
+
+    // GP JSON, an array of flat objects or a single one. Values can be strings or numbers
+    pub fn parse_json(contents: &str) -> Result<Vec<Self>, OmmError>
+    {
+        let value: serde_json::Value = serde_json::from_str(contents).map_err(|e| OmmError::Json(e.to_string()))?;
+
+        let objects = match value {
+            serde_json::Value::Array(objects) => objects,
+            object => vec![object],
+        };
+
+        let mut messages = Vec::new();
+
+        for object in objects {
+            let object = object.as_object().ok_or(OmmError::Json(String::from("expected an object")))?;
+
+            let fields = object
+                .iter()
+                .filter_map(|(key, value)| {
+                    let value = match value {
+                        serde_json::Value::String(s) => s.clone(),
+                        serde_json::Value::Number(n) => n.to_string(),
+                        _ => return None,
+                    };
+
+                    Some((key.clone(), value))
+                })
+                .collect();
+
+            messages.push(Self::new(fields));
+        }
+
+        Ok(messages)
+    }
+
+    // GP CSV, the first row has the keywords
+    pub fn parse_csv(contents: &str) -> Result<Vec<Self>, OmmError>
+    {
+        let mut rows = contents.lines().enumerate().filter(|(_, line)| !line.trim().is_empty());
+
+        let (_, header) = rows.next().ok_or(OmmError::Csv { row: 1 })?;
+        let header = Self::split_csv_row(header);
+        let mut messages = Vec::new();
+
+        for (i, row) in rows {
+            let values = Self::split_csv_row(row);
+
+            if values.len() != header.len() {
+                return Err(OmmError::Csv { row: i + 1 });
+            }
+
+            messages.push(Self::new(header.iter().cloned().zip(values).collect()));
+        }
+
+        Ok(messages)
+    }
+
+    // Values with commas, like some object names, are between double quotes
+    fn split_csv_row(row: &str) -> Vec<String>
+    {
+        let mut values = Vec::new();
+        let mut current = String::new();
+        let mut quoted = false;
+
+        for c in row.chars() {
+            match c {
+                '"' => quoted = !quoted,
+                ',' if !quoted => values.push(std::mem::take(&mut current).trim().to_string()),
+                _ => current.push(c),
+            }
+        }
+
+        values.push(current.trim().to_string());
+
+        values
+    }
+
+    // Message with the element data of a TLE, as the General Perturbations data of Space-Track
+    pub fn from_tle(tle: &TLE) -> Result<Self, OmmError>
+    {
+        let mut fields = HashMap::new();
+
+        let mut insert = |key: &str, value: String| {
+            fields.insert(key.to_string(), value);
+        };
+
+        insert("CCSDS_OMM_VERS", String::from("2.0"));
+        insert("CREATION_DATE", Utc::now().format("%Y-%m-%dT%H:%M:%S").to_string());
+        insert("ORIGINATOR", String::from("SEQUISAT"));
+
+        // OBJECT_NAME is mandatory, UNKNOWN is the placeholder of the standard for element sets without a name
+        insert("OBJECT_NAME", tle.get_name().unwrap_or(Self::UNKNOWN_NAME).to_string());
+        if tle.launch_year != 0 {
+            insert("OBJECT_ID", format!("{}-{:03}{}", tle.launch_year, tle.launch_number, tle.launch_piece));
+        }
+        insert("CENTER_NAME", String::from("EARTH"));
+        insert("REF_FRAME", String::from("TEME"));
+        insert("TIME_SYSTEM", String::from("UTC"));
+        insert("MEAN_ELEMENT_THEORY", String::from("SGP4"));
+
+        insert("EPOCH", tle.epoch.format("%Y-%m-%dT%H:%M:%S%.6f").to_string());
+        insert("MEAN_MOTION", format!("{:.8}", (tle.mean_motion * 1440.0) / (2.0*core::f64::consts::PI)));
+        insert("ECCENTRICITY", format!("{:.7}", tle.eccentricity));
+        insert("INCLINATION", format!("{:.4}", tle.inclination.to_degrees()));
+        insert("RA_OF_ASC_NODE", format!("{:.4}", tle.right_ascension.to_degrees()));
+        insert("ARG_OF_PERICENTER", format!("{:.4}", tle.argument_of_perigee.to_degrees()));
+        insert("MEAN_ANOMALY", format!("{:.4}", tle.mean_anomaly.to_degrees()));
+
+        insert("EPHEMERIS_TYPE", tle.ephemeris_type.to_string());
+        insert("CLASSIFICATION_TYPE", TLE::classification_code(&tle.classification).to_string());
+        insert("NORAD_CAT_ID", tle.catalog_number.to_string());
+        insert("ELEMENT_SET_NO", tle.element_set_number.to_string());
+        insert("REV_AT_EPOCH", tle.revolution_number.to_string());
+        insert("BSTAR", tle.drag_term.to_string());
+        insert("MEAN_MOTION_DOT", tle.ballistic_coefficient.to_string());
+        insert("MEAN_MOTION_DDOT", tle.mean_motion_ddot.to_string());
+
+        Ok(Self::new(fields))
+    }
+
+    // Message as "KEYWORD = value" lines
+    pub fn to_kvn(&self) -> String
+    {
+        let mut kvn = String::new();
+
+        let sections = [&["CCSDS_OMM_VERS"][..], &Self::HEADER, &Self::METADATA, &Self::MEAN_ELEMENTS, &Self::TLE_PARAMETERS];
+
+        for key in sections.concat() {
+            if let Some(value) = self.get(key) {
+                kvn.push_str(&format!("{:<20}= {}\n", key, value));
+            }
+        }
+
+        kvn
+    }
+
+    // Message as a CCSDS NDM/XML document
+    pub fn to_xml(&self) -> String
+    {
+        let elements = |keys: &[&str], indent: &str| -> String {
+            keys.iter()
+                .filter_map(|key| self.get(key).map(|value| format!("{}<{}>{}</{}>\n", indent, key, Self::escape_xml(value), key)))
+                .collect()
+        };
+
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+
+        xml.push_str("<ndm>\n");
+        xml.push_str(&format!("  <omm id=\"CCSDS_OMM_VERS\" version=\"{}\">\n", self.get("CCSDS_OMM_VERS").unwrap_or("2.0")));
+        xml.push_str("    <header>\n");
+        xml.push_str(&elements(&Self::HEADER, "      "));
+        xml.push_str("    </header>\n");
+        xml.push_str("    <body>\n      <segment>\n        <metadata>\n");
+        xml.push_str(&elements(&Self::METADATA, "          "));
+        xml.push_str("        </metadata>\n        <data>\n          <meanElements>\n");
+        xml.push_str(&elements(&Self::MEAN_ELEMENTS, "            "));
+        xml.push_str("          </meanElements>\n          <tleParameters>\n");
+        xml.push_str(&elements(&Self::TLE_PARAMETERS, "            "));
+        xml.push_str("          </tleParameters>\n        </data>\n");
+        xml.push_str("      </segment>\n    </body>\n  </omm>\n</ndm>\n");
+
+        xml
+    }
+
+    fn escape_xml(value: &str) -> String
+    {
+        return value.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;");
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str>
+    {
+        return self.fields.get(key).map(|value| value.as_str()).filter(|value| !value.is_empty());
+    }
+
+    fn get_number<T: std::str::FromStr>(&self, key: &'static str) -> Result<T, OmmError>
+    {
+        let value = self.get(key).ok_or(OmmError::MissingField(key))?;
+
+        return value.parse::<T>().map_err(|_| OmmError::InvalidField(key));
+    }
+
+    // Optional keywords are zero when missing
+    fn get_number_or_default<T: std::str::FromStr + Default>(&self, key: &'static str) -> Result<T, OmmError>
+    {
+        match self.get(key) {
+            Some(_) => self.get_number(key),
+            None => Ok(T::default()),
+        }
+    }
+
+    fn get_angle(&self, key: &'static str) -> Result<f64, OmmError>
+    {
+        return Ok(self.get_number::<f64>(key)?.to_radians());
+    }
+
+    // Launch year, launch number and piece of an international designator as "1998-067A"
+    fn parse_object_id(id: &str) -> Option<(i32, i32, String)>
+    {
+        let digits = |text: &str| text.bytes().all(|b| b.is_ascii_digit());
+
+        let year = id.get(..4).filter(|year| digits(year))?;
+        let number = id.get(5..8).filter(|number| digits(number))?;
+        let piece = id.get(8..).filter(|piece| (1..=3).contains(&piece.len()) && piece.bytes().all(|b| b.is_ascii_alphanumeric()))?;
+
+        if id.as_bytes()[4] != b'-' {
+            return None;
+        }
+
+        Some((year.parse().ok()?, number.parse().ok()?, piece.to_string()))
+    }
+
+    // Element data of the message, in the same units as the ones read from a TLE
+    pub fn to_tle(&self) -> Result<TLE, OmmError>
+    {
+        let epoch = self.get("EPOCH").ok_or(OmmError::MissingField("EPOCH"))?;
+        let epoch = Self::EPOCH_FORMATS
+            .iter()
+            .find_map(|format| NaiveDateTime::parse_from_str(epoch, format).ok())
+            .ok_or(OmmError::InvalidField("EPOCH"))?;
+
+        // Day of the year with the fraction of the day, being 1.0 the start of January 1st
+        let seconds = epoch.num_seconds_from_midnight() as f64 + epoch.nanosecond() as f64 / 1.0e9;
+        let epoch_day = epoch.ordinal() as f64 + seconds / 86400.0;
+
+        // International designator, left blank when the message has none
+        let (launch_year, launch_number, launch_piece) = match self.get("OBJECT_ID") {
+            Some(id) if !id.trim().is_empty() => Self::parse_object_id(id.trim()).ok_or(OmmError::InvalidField("OBJECT_ID"))?,
+            _ => (0, 0, String::new()),
+        };
+
+        Ok(TLE {
+            name: self.get("OBJECT_NAME").filter(|name| *name != Self::UNKNOWN_NAME).map(|name| name.to_string()),
+            warnings: Vec::new(),
+            catalog_number: self.get_number_or_default::<i32>("NORAD_CAT_ID")?,
+            classification: TLE::classification(self.get("CLASSIFICATION_TYPE").unwrap_or("U")).to_string(),
+            launch_year: launch_year,
+            launch_number: launch_number,
+            launch_piece: launch_piece,
+            epoch_year: epoch.year(),
+            epoch_day: epoch_day,
+            epoch: epoch.and_utc(),
+            epoch_jd: JulianDate::from_datetime(epoch.and_utc()),
+            ballistic_coefficient: self.get_number_or_default::<f64>("MEAN_MOTION_DOT")?,
+            mean_motion_ddot: self.get_number_or_default::<f64>("MEAN_MOTION_DDOT")?,
+            drag_term: self.get_number_or_default::<f64>("BSTAR")?,
+            ephemeris_type: self.get_number_or_default::<u8>("EPHEMERIS_TYPE")?,
+            element_set_number: self.get_number_or_default::<i32>("ELEMENT_SET_NO")?,
+            inclination: self.get_angle("INCLINATION")?,
+            right_ascension: self.get_angle("RA_OF_ASC_NODE")?,
+            eccentricity: self.get_number::<f64>("ECCENTRICITY")?,
+            argument_of_perigee: self.get_angle("ARG_OF_PERICENTER")?,
+            mean_anomaly: self.get_angle("MEAN_ANOMALY")?,
+            mean_motion: (self.get_number::<f64>("MEAN_MOTION")? * 2.0*core::f64::consts::PI) / 1440.0,
+            revolution_number: self.get_number_or_default::<i32>("REV_AT_EPOCH")?
+        })
+    }
+}
@@ -1,7 +1,9 @@
+use super::time::JulianDate;
 use super::tle::TLE;
 
 // Cow is fot data that is often read but occasionally mutated.
 // TODO: implement Cow Cow<'static, f64>
+#[derive(Clone)]
 pub struct Orbit {
     /// B star
     pub drag_term: f64,
@@ -23,6 +25,9 @@ pub struct Orbit {
 
     /// Mean number of orbits per day in rad.min⁻¹
     pub mean_motion: f64,
+
+    /// Julian date of the epoch of the elements
+    pub epoch: JulianDate,
 }
 
 impl Orbit{
@@ -35,10 +40,8 @@ impl Orbit{
             eccentricity: tle.eccentricity,
             argument_of_perigee: tle.argument_of_perigee,
             mean_anomaly: tle.mean_anomaly,
-            mean_motion: tle.mean_motion
+            mean_motion: tle.mean_motion,
+            epoch: tle.epoch_jd
         }
     }
 }
-
-
-
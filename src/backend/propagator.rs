@@ -1,229 +1,365 @@
-
+use super::gravity::{GravityConstants, GravityModel};
 use super::orbit::Orbit;
+use super::time;
+use super::coordinates::Teme;
 use super::vector::PositionVector;
 
-const KE: f64 = 0.07436685316871385;
-const S: f64 = 1.0122292763545218;
-const ER: f64 = 6371.0;
-const Q0MS2T: f64 = 0.00000000188027916;
-const J2: f64 = 0.00108262998905;     // Second Gravitational Zonal Harmonic of the Earth
-const J3: f64 = -0.00000253215306;   // Third Gravitational Zonal Harmonic of the Earth
-const J4: f64 = -0.00000161098761;   // Forth Gravitational Zonal Harmonic of the Earth
-const AE: f64 = 1.0;            // Equatorial radius of t ehe earth
-const K2: f64 = 0.5*J2*AE*AE;
-const K4: f64 = (-3.0/8.0)*J4*AE*AE*AE*AE;
-const A30: f64 = -J3*AE*AE*AE;
+use core::f64::consts::PI;
+use std::fmt;
+
+const X2O3: f64 = 2.0 / 3.0;
+const TEMP4: f64 = 1.5e-12;             // Divisor used to avoid a division by zero at 180 deg of inclination
+
+// Orbits with a period of 225 minutes or more need the deep space perturbations
+const DEEP_SPACE_PERIOD: f64 = 225.0;
+
+// Lunar and solar constants used by the deep space perturbations
+const ZNS: f64 = 1.19459e-5;            // Mean motion of the sun in rad/min
+const ZES: f64 = 0.01675;               // Eccentricity of the earth orbit
+const ZNL: f64 = 1.5835218e-4;          // Mean motion of the moon in rad/min
+const ZEL: f64 = 0.05490;               // Eccentricity of the moon orbit
+const RPTIM: f64 = 4.3752690880113e-3;  // Earth rotation rate in rad/min
 
 pub trait Propagate {
     fn initialize(&mut self);
-    fn propagate(&mut self, delta_time: f64) -> PositionVector;
+
+    /// Returns the TEME position (km) and velocity (km/s) at delta_time minutes since epoch
+    fn propagate(&mut self, delta_time: f64) -> Result<Teme, PropagationError>;
+}
+
+// Errors of the propagation, they follow the classic SGP4 error codes
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PropagationError {
+    /// Mean eccentricity is not within the range [0, 1)
+    MeanEccentricity,
+
+    /// Mean motion is less than or equal to zero
+    MeanMotion,
+
+    /// Perturbed eccentricity is not within the range [0, 1]
+    PerturbedEccentricity,
+
+    /// Semi-latus rectum is negative
+    SemiLatusRectum,
+
+    /// Orbit has decayed, the satellite is below the surface of the earth
+    Decayed,
+}
+
+impl PropagationError {
+    pub fn code(&self) -> u8
+    {
+        match self {
+            PropagationError::MeanEccentricity => 1,
+            PropagationError::MeanMotion => 2,
+            PropagationError::PerturbedEccentricity => 3,
+            PropagationError::SemiLatusRectum => 4,
+            PropagationError::Decayed => 6,
+        }
+    }
+}
+
+impl fmt::Display for PropagationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        let msg = match self {
+            PropagationError::MeanEccentricity => "Mean eccentricity out of range",
+            PropagationError::MeanMotion => "Mean motion is negative",
+            PropagationError::PerturbedEccentricity => "Perturbed eccentricity out of range",
+            PropagationError::SemiLatusRectum => "Semi-latus rectum is negative",
+            PropagationError::Decayed => "Orbit has decayed",
+        };
+
+        write!(f, "{} (code {})", msg, self.code())
+    }
 }
 
+impl std::error::Error for PropagationError {}
+
+// Picks the propagator for the orbit, SDP4 for periods of 225 minutes or more and SGP4 otherwise
+pub fn select_propagator(orbit: Orbit, model: GravityModel) -> Box<dyn Propagate>
+{
+    if SDP4::is_deep_space(&orbit, model) {
+        Box::new(SDP4::with_gravity_model(orbit, model))
+    } else {
+        Box::new(SGP4::with_gravity_model(orbit, model))
+    }
+}
+
+/*
+ * Implementation based on the revised SGP4 by Vallado et al. "Revisiting Spacetrack Report #3"
+ * (AIAA 2006-6753), the names of the coefficients follow the ones used in the paper where possible.
+*/
 pub struct SGP4 {
     pub orbit_0: Orbit,
-    semimayor_axis:    f64,
-    phita:  f64,
-    exilon: f64,
+    gravity: GravityConstants,
+    isimp:  bool,           // Simplified drag equations, used for perigees below 220 km
+    semimayor_axis: f64,
+    mean_motion:    f64,    // Un-Kozai'd mean motion
+    periodics: PeriodicCoefficients,
     eta:    f64,
-    beta0:  f64,
     c1:     f64,
-    c2:     f64,
-    c3:     f64,
     c4:     f64,
     c5:     f64,
     d2:     f64,
     d3:     f64,
     d4:     f64,
+    delmo:  f64,
+    sinmao: f64,
+    mdot:   f64,
+    argpdot: f64,
+    nodedot: f64,
+    omgcof: f64,
+    xmcof:  f64,
+    nodecf: f64,
+    t2cof:  f64,
+    t3cof:  f64,
+    t4cof:  f64,
+    t5cof:  f64,
 }
 
+/*
+ * Deep space extension of the SGP4 for orbits with periods of 225 minutes or more, adds the
+ * secular and periodic effects of the sun and the moon and the resonance effects of the earth
+ * gravity for 12h and 24h orbits.
+*/
 pub struct SDP4 {
-    eccentricity_dot: f64,
-    inclination_dot: f64,
+    near:   SGP4,
+    gsto:   f64,            // Greenwich sidereal time at epoch in rad
+    solar:  ThirdBodyPeriodics,
+    lunar:  ThirdBodyPeriodics,
+    resonance: Resonance,
+    dedt:   f64,
+    didt:   f64,
+    dmdt:   f64,
+    domdt:  f64,
+    dnodt:  f64,
+}
+
+// Coefficients of the short and long period periodics that only depend on the inclination
+struct PeriodicCoefficients {
+    sinio:  f64,
+    cosio:  f64,
+    con41:  f64,
+    x1mth2: f64,
+    x7thm1: f64,
+    xlcof:  f64,
+    aycof:  f64,
+}
+
+// Mean elements after applying the secular effects, the angles are in rad
+struct MeanElements {
+    mean_motion:    f64,
+    semimayor_axis: f64,
+    eccentricity:   f64,
+    inclination:    f64,
+    right_ascension: f64,
+    argument_of_perigee: f64,
+    mean_anomaly:   f64,
+}
+
+// Intermediate values of the secular update, before the deep space effects are applied
+struct SecularUpdate {
+    mean_anomaly:   f64,
+    argument_of_perigee: f64,
+    right_ascension: f64,
+    tempa:  f64,
+    tempe:  f64,
+    templ:  f64,
+}
+
+// Terms of the perturbations due to a third body (sun or moon) over the orbit, see dscom
+struct ThirdBodyTerms {
+    s1: f64, s2: f64, s3: f64, s4: f64, s5: f64, s6: f64, s7: f64,
+    z1: f64, z2: f64, z3: f64,
+    z11: f64, z12: f64, z13: f64,
+    z21: f64, z22: f64, z23: f64,
+    z31: f64, z32: f64, z33: f64,
+}
+
+// Coefficients of the long period periodics due to a third body, see dpper
+struct ThirdBodyPeriodics {
+    zmo: f64,               // Mean anomaly of the body at epoch
+    zn:  f64,               // Mean motion of the body
+    ze:  f64,               // Eccentricity of the body orbit
+    e2: f64, e3: f64,
+    i2: f64, i3: f64,
+    l2: f64, l3: f64, l4: f64,
+    gh2: f64, gh3: f64, gh4: f64,
+    h2: f64, h3: f64,
+}
+
+// Geopotential resonance terms for 12h (half-day) and 24h (synchronous) orbits, see dsinit
+#[derive(PartialEq)]
+enum ResonanceKind {
+    None,
+    Synchronous,
+    HalfDay,
+}
+
+struct Resonance {
+    kind:   ResonanceKind,
+    d2201: f64, d2211: f64, d3210: f64, d3222: f64, d4410: f64,
+    d4422: f64, d5220: f64, d5232: f64, d5421: f64, d5433: f64,
+    del1:  f64, del2: f64, del3: f64,
+    xfact: f64,
+    xlamo: f64,
+    // State of the numerical integrator
+    atime: f64,
+    xli:   f64,
+    xni:   f64,
 }
 
 impl Propagate for SGP4 {
 
-    // TODO: Separate into smaller functions and comment/document it
     fn initialize(&mut self)
     {
+        let er = self.gravity.radius;
+        let j2 = self.gravity.j2;
+        let ss = 78.0 / er + 1.0;
+        let qzms2t = ((120.0 - 78.0) / er).powi(4);
+
+        let ecco = self.orbit_0.eccentricity;
+
         self.recover_a02_n02();
+        self.periodics = PeriodicCoefficients::new(self.orbit_0.inclination, &self.gravity);
+
+        let eccsq = ecco * ecco;
+        let omeosq = 1.0 - eccsq;
+        let rteosq = omeosq.sqrt();
+        let cosio = self.periodics.cosio;
+        let cosio2 = cosio * cosio;
+        let sinio = self.periodics.sinio;
+        let con41 = self.periodics.con41;
+
+        let ao = self.semimayor_axis;
+        let po = ao * omeosq;
+        let con42 = 1.0 - 5.0 * cosio2;
+        let posq = po * po;
+        let rp = ao * (1.0 - ecco);
+
+        self.isimp = rp < (220.0 / er + 1.0);
+
+        // For perigees below 156 km the values of s and qoms2t are altered
+        let mut sfour = ss;
+        let mut qzms24 = qzms2t;
+        let perige = (rp - 1.0) * er;
 
-        self.phita = self.orbit_0.inclination.cos();
-        self.exilon = 1.0 / (self.semimayor_axis - S);
-        self.beta0 = (1.0 - self.orbit_0.eccentricity.powi(2)).sqrt();
-        self.eta = self.semimayor_axis * self.orbit_0.eccentricity * self.exilon;
+        if perige < 156.0 {
+            sfour = if perige < 98.0 { 20.0 } else { perige - 78.0 };
+            qzms24 = ((120.0 - sfour) / er).powi(4);
+            sfour = sfour / er + 1.0;
+        }
+
+        let pinvsq = 1.0 / posq;
+        let tsi = 1.0 / (ao - sfour);
+        self.eta = ao * ecco * tsi;
+        let etasq = self.eta * self.eta;
+        let eeta = ecco * self.eta;
+        let psisq = (1.0 - etasq).abs();
+        let coef = qzms24 * tsi.powi(4);
+        let coef1 = coef / psisq.powf(3.5);
+        let no = self.mean_motion;
+        let bstar = self.orbit_0.drag_term;
 
-        let psisq = (1.0 - self.eta*self.eta).abs();
+        let c2 = coef1 * no * (ao * (1.0 + 1.5 * etasq + eeta * (4.0 + etasq))
+            + 0.375 * j2 * tsi / psisq * con41 * (8.0 + 3.0 * etasq * (8.0 + etasq)));
+        self.c1 = bstar * c2;
 
-        let coef = Q0MS2T*self.exilon.powi(4);
-        let coef1 = coef / (psisq.powf(3.5));
+        let c3 = if ecco > 1.0e-4 {
+            -2.0 * coef * tsi * self.gravity.j3oj2 * no * sinio / ecco
+        } else {
+            0.0
+        };
 
-        self.c2 = coef1 * self.orbit_0.mean_motion
-            * (self.semimayor_axis*(1.0 + 1.5*self.eta*self.eta + 4.0*self.orbit_0.eccentricity*self.eta + self.orbit_0.eccentricity*self.eta.powi(3))
-            + 1.5*((K2*self.exilon)/(1.0-self.eta*self.eta))
-            * (-0.5+1.5*self.phita*self.phita) * (8.0 + 24.0*self.eta*self.eta + 3.0*self.eta.powi(4)));
+        self.c4 = 2.0 * no * coef1 * ao * omeosq
+            * (self.eta * (2.0 + 0.5 * etasq) + ecco * (0.5 + 2.0 * etasq)
+            - j2 * tsi / (ao * psisq)
+            * (-3.0 * con41 * (1.0 - 2.0 * eeta + etasq * (1.5 - 0.5 * eeta))
+            + 0.75 * self.periodics.x1mth2 * (2.0 * etasq - eeta * (1.0 + etasq)) * (2.0 * self.orbit_0.argument_of_perigee).cos()));
+        self.c5 = 2.0 * coef1 * ao * omeosq * (1.0 + 2.75 * (etasq + eeta) + eeta * etasq);
 
-        self.c1 = self.orbit_0.drag_term*self.c2;
+        // Secular rates due to the zonal harmonics
+        let cosio4 = cosio2 * cosio2;
+        let temp1 = 1.5 * j2 * pinvsq * no;
+        let temp2 = 0.5 * temp1 * j2 * pinvsq;
+        let temp3 = -0.46875 * self.gravity.j4 * pinvsq * pinvsq * no;
 
-        self.c3 = (coef * self.exilon * A30 * self.orbit_0.mean_motion * AE * self.orbit_0.inclination.sin()) / (K2*self.orbit_0.eccentricity);
+        self.mdot = no + 0.5 * temp1 * rteosq * con41
+            + 0.0625 * temp2 * rteosq * (13.0 - 78.0 * cosio2 + 137.0 * cosio4);
+        self.argpdot = -0.5 * temp1 * con42 + 0.0625 * temp2 * (7.0 - 114.0 * cosio2 + 395.0 * cosio4)
+            + temp3 * (3.0 - 36.0 * cosio2 + 49.0 * cosio4);
+        let xhdot1 = -temp1 * cosio;
+        self.nodedot = xhdot1 + (0.5 * temp2 * (4.0 - 19.0 * cosio2) + 2.0 * temp3 * (3.0 - 7.0 * cosio2)) * cosio;
 
-        self.c4 = 2.0*self.orbit_0.mean_motion * coef1 * self.semimayor_axis * self.beta0*self.beta0 
-            * ((2.0*self.eta*(1.0 + self.orbit_0.eccentricity*self.eta) + 0.5*self.orbit_0.eccentricity + 0.5*self.eta.powi(3))
-            - ((2.0*K2*self.exilon) / (self.semimayor_axis*psisq))
-            * (3.0*(1.0 - 3.0*self.phita*self.phita) * (1.0 + 1.5*self.eta*self.eta - 2.0*self.orbit_0.eccentricity*self.eta - 0.5*self.orbit_0.eccentricity*self.eta.powi(3))
-            + 0.75*(1.0-self.phita*self.phita)
-            * (2.0*self.eta*self.eta - self.orbit_0.eccentricity*self.eta - self.orbit_0.eccentricity*self.eta.powi(3)) * (2.0*self.orbit_0.argument_of_perigee).cos()));
-        
-        self.c5 = 2.0 * coef1 * self.semimayor_axis * self.beta0*self.beta0 
-            * (1.0 + (11.0/4.0)*self.eta*(self.eta + self.orbit_0.eccentricity) + self.orbit_0.eccentricity*self.eta.powi(3));
+        self.omgcof = bstar * c3 * self.orbit_0.argument_of_perigee.cos();
+        self.xmcof = if ecco > 1.0e-4 { -X2O3 * coef * bstar / eeta } else { 0.0 };
+        self.nodecf = 3.5 * omeosq * xhdot1 * self.c1;
+        self.t2cof = 1.5 * self.c1;
 
-        self.d2 = 4.0 * self.semimayor_axis * self.exilon * self.c1*self.c1;
-        self.d3 = (4.0/3.0) * self.semimayor_axis * self.exilon*self.exilon * (17.0*self.semimayor_axis + S) * self.c1.powi(3);
-        self.d4 = (2.0/3.0) * self.semimayor_axis * self.exilon.powi(3) * (221.0*self.semimayor_axis + 32.0*S) * self.c1.powi(4);
+        self.delmo = (1.0 + self.eta * self.orbit_0.mean_anomaly.cos()).powi(3);
+        self.sinmao = self.orbit_0.mean_anomaly.sin();
 
+        // Higher order drag terms, not used with the simplified equations
+        if !self.isimp {
+            let c1sq = self.c1 * self.c1;
+            self.d2 = 4.0 * ao * tsi * c1sq;
+            let temp = self.d2 * tsi * self.c1 / 3.0;
+            self.d3 = (17.0 * ao + sfour) * temp;
+            self.d4 = 0.5 * temp * ao * tsi * (221.0 * ao + 31.0 * sfour) * self.c1;
+            self.t3cof = self.d2 + 2.0 * c1sq;
+            self.t4cof = 0.25 * (3.0 * self.d3 + self.c1 * (12.0 * self.d2 + 10.0 * c1sq));
+            self.t5cof = 0.2 * (3.0 * self.d4 + 12.0 * self.c1 * self.d3 + 6.0 * self.d2 * self.d2
+                + 15.0 * c1sq * (2.0 * self.d2 + c1sq));
+        }
     }
 
     // Note: this provides the coordinates in TEME, meaning that it doesnt have an earth-fixed frame, that would be the ECEF
-    fn propagate(&mut self, delta_time: f64) -> PositionVector
+    fn propagate(&mut self, delta_time: f64) -> Result<Teme, PropagationError>
     {
-        let mdf = self.orbit_0.mean_anomaly + (1.0 + (3.0*K2 * (-1.0+3.0*self.phita*self.phita))/(2.0*self.semimayor_axis*self.semimayor_axis*self.beta0.powi(3))
-            + (3.0*K2*K2 * (13.0 - 78.0*self.phita*self.phita + 137.0*self.phita.powi(4)))/(16.0*self.semimayor_axis.powi(4)*self.beta0.powi(7)))
-            * self.orbit_0.mean_motion*delta_time;
- 
-        let wdf = self.orbit_0.argument_of_perigee + (-(3.0*K2 * (1.0-5.0*self.phita*self.phita))/(2.0*self.semimayor_axis*self.semimayor_axis*self.beta0.powi(4)) 
-            + (3.0*K2*K2 * (7.0 - 114.0*self.phita*self.phita + 395.0*self.phita.powi(4)))/(16.0*self.semimayor_axis.powi(4)*self.beta0.powi(7))
-            + (5.0*K4 * (3.0-36.0*self.phita*self.phita+49.0*self.phita.powi(4)))/(4.0*self.semimayor_axis.powi(4)*self.beta0.powi(8)))
-            * self.orbit_0.mean_motion*delta_time;
-
-        let omegadf = self.orbit_0.right_ascension + (-( 3.0*K2*self.phita ) / ( self.semimayor_axis*self.semimayor_axis*self.beta0.powi(4) )
-            + ( 3.0*K2*K2*(4.0*self.phita - 19.0*self.phita.powi(3)) ) / ( 2.0*self.semimayor_axis.powi(4)*self.beta0.powi(8) )
-            + (5.0*K4*self.phita*(3.0 - 7.0*self.phita*self.phita))/(2.0*self.semimayor_axis.powi(4)*self.beta0.powi(8)))
-            * self.orbit_0.mean_motion*delta_time;
-
-        let deltaw = self.orbit_0.drag_term * self.c3 * self.orbit_0.argument_of_perigee.cos() * delta_time;
-        let delta_m = -(2.0/3.0) * Q0MS2T * self.orbit_0.drag_term * self.exilon.powi(4)
-                * (AE / (self.orbit_0.eccentricity*self.eta)) 
-                * ((1.0 + self.eta*mdf.cos()).powi(3) - (1.0 + self.eta*self.orbit_0.mean_anomaly.cos()).powi(3));
-
-        let mp = mdf + deltaw + delta_m;
-
-        let w = wdf - deltaw - delta_m;
-        let omega = omegadf - 10.5*((self.orbit_0.mean_motion*K2*self.phita) / (self.semimayor_axis*self.semimayor_axis*self.beta0*self.beta0))
-                * self.c1*delta_time*delta_time;
-        let e = self.orbit_0.eccentricity - self.orbit_0.drag_term*self.c4*delta_time
-            - self.orbit_0.drag_term*self.c5*(mp.sin() - self.orbit_0.mean_anomaly.sin()); // Does using radians instead of degrees affect the propagation?
-        let a = self.semimayor_axis*(1.0 - self.c1*delta_time - self.d2*delta_time*delta_time - self.d3*delta_time.powi(3)
-            - self.d4*delta_time.powi(4)).powi(2);
-
-        let il = mp + w + omega + self.orbit_0.mean_motion*(1.5*self.c1*delta_time*delta_time + (self.d2+2.0*self.c1*self.c1)*delta_time.powi(3)
-            + 0.25*(3.0*self.d3 + 12.0*self.c1*self.d2 + 10.0*self.c1.powi(3))*delta_time.powi(4)
-            + 0.2*(3.0*self.d4 + 12.0*self.c1*self.d3 + 6.0*self.d2*self.d2 + 30.0*self.c1*self.c1*self.d2 + 15.0*self.c1.powi(4))
-            *delta_time.powi(5));
-
-        let b = (1.0-e*e).sqrt();
-        //let n = KE / a.powf(1.5);
-
-        //Add the long-period periodic terms
-
-        let axn = e*w.cos(); // TODO: change value depending on the perigee (see spacetrk.pdf)
-
-        let ill = ((A30*self.orbit_0.inclination.sin()) / (8.0*K2*a*b*b)) * axn
-            * ((3.0+5.0*self.phita) / (1.0+self.phita));
-        let aynl = (A30*self.orbit_0.inclination.sin()) / (4.0*K2*a*b*b);
-        let ilt = il + ill;
-        let ayn = e*w.sin() + aynl; 
-
-        // Solve Kepler's equation for (E + w) by defining:
-        let up = (ilt - omega) % (2.0 * core::f64::consts::PI);    // Going to be reused later, not for U 
-
-        let mut eo1 = up;
-
-        // And using the iterator equation with:
-        //   the following iteration needs better limits on corrections
-		for _ in 0..10 
-		{
-            let delta = (up - ayn*eo1.cos() + axn*eo1.sin() - eo1)
-                / (-ayn*eo1.sin() - axn*eo1.cos() + 1.0);
+        let secular = self.secular_update(delta_time);
 
-            if delta.abs() < 1.0e-12 {
-                break;
-            }
+        let elements = self.mean_elements(&secular, self.mean_motion,
+            self.orbit_0.eccentricity, self.orbit_0.inclination)?;
 
-			eo1 += if delta < -0.95 {
-                -0.95
-            } else if delta > 0.95 {
-                0.95
-            } else {
-                delta
-            };
-		}
-
-        // Calculate preliminary quantities needed por short-period periodics
-        let ecose = axn*eo1.cos() + ayn*eo1.sin();
-		let esine = axn*eo1.sin() - ayn*eo1.cos();
-		let el2 = axn*axn + ayn*ayn;
-		let pl = a*(1.0 - el2);
-
-        let r = a*(1.0 - ecose);
-
-        //let rdot = KE*(a.sqrt()/r) * esine;
-        // let rfdot = KE*(pl.sqrt()/r);
-
-        let cosu = (a/r) * (eo1.cos() - axn + ayn*(esine / (1.0 + (1.0 - el2).sqrt())));
-        let sinu = (a/r) * (eo1.sin() - ayn - axn*(esine / (1.0 + (1.0 - el2).sqrt())));
-
-        let u =  sinu.atan2(cosu);
-
-        let deltar = (K2 / (2.0*pl)) * (1.0-self.phita*self.phita) * (2.0*u).cos();
-        let deltau = -0.25 * (K2 / (pl*pl)) * (7.0*self.phita*self.phita - 1.0) * (2.0*u).sin();
-        let deltaomega = ((3.0*K2*self.phita) / (2.0*pl*pl)) * (2.0*u).sin();
-        let deltai = ((3.0*K2*self.phita) / (2.0*pl*pl)) * self.orbit_0.inclination.sin() * (2.0*u).cos();
-        //let deltardot = -((K2*n) / pl) * (1.0-self.phita*self.phita) * (2.0*u).sin(); // Note that n is the actual n and n is the greek n-like letter
-        //let deltarfdot = ((K2*n) / pl) * ((1.0-self.phita*self.phita) * (2.0*u).cos() 
-        //    - 1.5*(1.0 - 3.0*self.phita*self.phita));
-
-        // The short period periodics are added to give the osculation quantities
-        let rk = r*(1.0 - 1.5*K2*((1.0 - el2).sqrt() / (pl*pl)) * (3.0*self.phita*self.phita - 1.0)) + deltar;
-        let uk = u + deltau;
-        let omegak = omega + deltaomega;
-        let ik = self.orbit_0.inclination + deltai;
-        //let rdotk = rdot + deltardot;
-        //let rfdotk = rfdot + deltarfdot;
-
-        let ux = -omegak.sin() * ik.cos() * uk.sin()
-            + omegak.cos() * uk.cos();
-        let uy = omegak.cos() * ik.cos() * uk.sin()
-            + omegak.sin() * uk.cos();
-        let uz = ik.sin() * uk.sin();
-        
-
-        let rx = rk * ux;
-        let ry = rk * uy;
-        let rz = rk * uz;
-
-        PositionVector::new(rx*ER, ry*ER, rz*ER)
+        self.periodics(&elements, &self.periodics)
     }
-
 }
 
 impl SGP4 {
     pub fn new(orbit_0 :Orbit) -> Self
+    {
+        Self::with_gravity_model(orbit_0, GravityModel::default())
+    }
+
+    pub fn with_gravity_model(orbit_0: Orbit, model: GravityModel) -> Self
     {
         SGP4 {
             orbit_0: orbit_0,
-            semimayor_axis:    0.0, 
-            phita:  0.0,
-            exilon:  0.0,
-            eta:     0.0,
-            beta0:     0.0,
+            gravity: model.constants(),
+            isimp:  false,
+            semimayor_axis: 0.0,
+            mean_motion:    0.0,
+            periodics: PeriodicCoefficients::new(0.0, &model.constants()),
+            eta:    0.0,
             c1:     0.0,
-            c2:     0.0,
-            c3:     0.0,
             c4:     0.0,
             c5:     0.0,
             d2:     0.0,
             d3:     0.0,
             d4:     0.0,
+            delmo:  0.0,
+            sinmao: 0.0,
+            mdot:   0.0,
+            argpdot: 0.0,
+            nodedot: 0.0,
+            omgcof: 0.0,
+            xmcof:  0.0,
+            nodecf: 0.0,
+            t2cof:  0.0,
+            t3cof:  0.0,
+            t4cof:  0.0,
+            t5cof:  0.0,
         }
     }
 
@@ -231,31 +367,807 @@ impl SGP4 {
     {
         println!("\nSGP4 Model Data: ");
         println!("C1:  {}", self.c1);
-        println!("C2:  {}", self.c2);
-        println!("C3:  {}", self.c3);
         println!("C4:  {}", self.c4);
         println!("C5:  {}", self.c5);
         println!("D2:  {}", self.d2);
         println!("D3:  {}", self.d3);
         println!("D4:  {}", self.d4);
     }
-    
-    pub fn recover_a02_n02(&mut self) 
+
+    // Recovers the original mean motion and semimayor axis from the Kozai mean motion of the TLE
+    pub fn recover_a02_n02(&mut self)
+    {
+        let ecco = self.orbit_0.eccentricity;
+        let cosio = self.orbit_0.inclination.cos();
+        let omeosq = 1.0 - ecco * ecco;
+
+        let ak = (self.gravity.xke / self.orbit_0.mean_motion).powf(X2O3);
+        let d1 = 0.75 * self.gravity.j2 * (3.0 * cosio * cosio - 1.0) / (omeosq.sqrt() * omeosq);
+
+        let del = d1 / (ak * ak);
+        let adel = ak * (1.0 - del * del - del * (1.0 / 3.0 + 134.0 * del * del / 81.0));
+        let del = d1 / (adel * adel);
+
+        self.mean_motion = self.orbit_0.mean_motion / (1.0 + del);
+        self.semimayor_axis = (self.gravity.xke / self.mean_motion).powf(X2O3);
+    }
+
+    // Period of the orbit in minutes, using the recovered mean motion
+    pub fn period(&self) -> f64
+    {
+        2.0 * PI / self.mean_motion
+    }
+
+    // Update for secular gravity and atmospheric drag
+    fn secular_update(&self, t: f64) -> SecularUpdate
+    {
+        let xmdf = self.orbit_0.mean_anomaly + self.mdot * t;
+        let argpdf = self.orbit_0.argument_of_perigee + self.argpdot * t;
+        let nodedf = self.orbit_0.right_ascension + self.nodedot * t;
+
+        let t2 = t * t;
+        let mut update = SecularUpdate {
+            mean_anomaly: xmdf,
+            argument_of_perigee: argpdf,
+            right_ascension: nodedf + self.nodecf * t2,
+            tempa: 1.0 - self.c1 * t,
+            tempe: self.orbit_0.drag_term * self.c4 * t,
+            templ: self.t2cof * t2,
+        };
+
+        if !self.isimp {
+            let delomg = self.omgcof * t;
+            let delm = self.xmcof * ((1.0 + self.eta * xmdf.cos()).powi(3) - self.delmo);
+            let temp = delomg + delm;
+            update.mean_anomaly = xmdf + temp;
+            update.argument_of_perigee = argpdf - temp;
+
+            let t3 = t2 * t;
+            let t4 = t3 * t;
+            update.tempa -= self.d2 * t2 + self.d3 * t3 + self.d4 * t4;
+            update.tempe += self.orbit_0.drag_term * self.c5 * (update.mean_anomaly.sin() - self.sinmao);
+            update.templ += self.t3cof * t3 + t4 * (self.t4cof + t * self.t5cof);
+        }
+
+        update
+    }
+
+    // Applies the drag effects on the semimayor axis and eccentricity and normalizes the angles
+    fn mean_elements(&self, secular: &SecularUpdate, nm: f64, em: f64, inclm: f64) -> Result<MeanElements, PropagationError>
+    {
+        if nm <= 0.0 {
+            return Err(PropagationError::MeanMotion);
+        }
+
+        let am = (self.gravity.xke / nm).powf(X2O3) * secular.tempa * secular.tempa;
+        let nm = self.gravity.xke / am.powf(1.5);
+        let em = em - secular.tempe;
+
+        if !(-0.001..1.0).contains(&em) {
+            return Err(PropagationError::MeanEccentricity);
+        }
+
+        let em = em.max(1.0e-6);
+
+        let mm = secular.mean_anomaly + self.mean_motion * secular.templ;
+        let xlm = (mm + secular.argument_of_perigee + secular.right_ascension) % (2.0 * PI);
+        let nodem = secular.right_ascension % (2.0 * PI);
+        let argpm = secular.argument_of_perigee % (2.0 * PI);
+
+        Ok(MeanElements {
+            mean_motion: nm,
+            semimayor_axis: am,
+            eccentricity: em,
+            inclination: inclm,
+            right_ascension: nodem,
+            argument_of_perigee: argpm,
+            mean_anomaly: (xlm - argpm - nodem) % (2.0 * PI),
+        })
+    }
+
+    // Adds the long period and short period periodics to the mean elements and returns the osculating state
+    fn periodics(&self, elements: &MeanElements, coefficients: &PeriodicCoefficients) -> Result<Teme, PropagationError>
+    {
+        let am = elements.semimayor_axis;
+        let ep = elements.eccentricity;
+        let nodep = elements.right_ascension;
+        let argpp = elements.argument_of_perigee;
+        let sinip = coefficients.sinio;
+        let cosip = coefficients.cosio;
+
+        // Long period periodics
+        let axnl = ep * argpp.cos();
+        let temp = 1.0 / (am * (1.0 - ep * ep));
+        let aynl = ep * argpp.sin() + temp * coefficients.aycof;
+        let xl = elements.mean_anomaly + argpp + nodep + temp * coefficients.xlcof * axnl;
+
+        // Solve Kepler's equation for (E + w)
+        let u = (xl - nodep) % (2.0 * PI);
+        let mut eo1 = u;
+        let mut sineo1 = eo1.sin();
+        let mut coseo1 = eo1.cos();
+
+        for _ in 0..10
+        {
+            sineo1 = eo1.sin();
+            coseo1 = eo1.cos();
+
+            let delta = (u - aynl * coseo1 + axnl * sineo1 - eo1)
+                / (1.0 - coseo1 * axnl - sineo1 * aynl);
+
+            eo1 += delta.clamp(-0.95, 0.95);
+
+            if delta.abs() < 1.0e-12 {
+                break;
+            }
+        }
+
+        // Calculate preliminary quantities needed for short-period periodics
+        let ecose = axnl * coseo1 + aynl * sineo1;
+        let esine = axnl * sineo1 - aynl * coseo1;
+        let el2 = axnl * axnl + aynl * aynl;
+        let pl = am * (1.0 - el2);
+
+        if pl < 0.0 {
+            return Err(PropagationError::SemiLatusRectum);
+        }
+
+        let rl = am * (1.0 - ecose);
+        let rdotl = am.sqrt() * esine / rl;
+        let rvdotl = pl.sqrt() / rl;
+        let betal = (1.0 - el2).sqrt();
+        let temp = esine / (1.0 + betal);
+        let sinu = am / rl * (sineo1 - aynl - axnl * temp);
+        let cosu = am / rl * (coseo1 - axnl + aynl * temp);
+        let su = sinu.atan2(cosu);
+        let sin2u = (cosu + cosu) * sinu;
+        let cos2u = 1.0 - 2.0 * sinu * sinu;
+        let temp = 1.0 / pl;
+        let temp1 = 0.5 * self.gravity.j2 * temp;
+        let temp2 = temp1 * temp;
+
+        // The short period periodics are added to give the osculating quantities
+        let nm = elements.mean_motion;
+        let con41 = coefficients.con41;
+        let x1mth2 = coefficients.x1mth2;
+
+        let mrt = rl * (1.0 - 1.5 * temp2 * betal * con41) + 0.5 * temp1 * x1mth2 * cos2u;
+        let su = su - 0.25 * temp2 * coefficients.x7thm1 * sin2u;
+        let xnode = nodep + 1.5 * temp2 * cosip * sin2u;
+        let xinc = elements.inclination + 1.5 * temp2 * cosip * sinip * cos2u;
+        let mvt = rdotl - nm * temp1 * x1mth2 * sin2u / self.gravity.xke;
+        let rvdot = rvdotl + nm * temp1 * (x1mth2 * cos2u + 1.5 * con41) / self.gravity.xke;
+
+        // Orientation vectors
+        let sinsu = su.sin();
+        let cossu = su.cos();
+        let snod = xnode.sin();
+        let cnod = xnode.cos();
+        let sini = xinc.sin();
+        let cosi = xinc.cos();
+        let xmx = -snod * cosi;
+        let xmy = cnod * cosi;
+
+        let ux = xmx * sinsu + cnod * cossu;
+        let uy = xmy * sinsu + snod * cossu;
+        let uz = sini * sinsu;
+        let vx = xmx * cossu - cnod * sinsu;
+        let vy = xmy * cossu - snod * sinsu;
+        let vz = sini * cossu;
+
+        if mrt < 1.0 {
+            return Err(PropagationError::Decayed);
+        }
+
+        // Position in km and velocity in km/s
+        let er = self.gravity.radius;
+        let vkmpersec = er * self.gravity.xke / 60.0;
+
+        Ok(Teme::new(
+            PositionVector::new(mrt * ux * er, mrt * uy * er, mrt * uz * er),
+            PositionVector::new(
+                (mvt * ux + rvdot * vx) * vkmpersec,
+                (mvt * uy + rvdot * vy) * vkmpersec,
+                (mvt * uz + rvdot * vz) * vkmpersec)
+        ))
+    }
+}
+
+impl Propagate for SDP4 {
+
+    fn initialize(&mut self)
+    {
+        self.near.initialize();
+
+        // Deep space orbits always use the simplified drag equations
+        self.near.isimp = true;
+
+        let orbit = &self.near.orbit_0;
+        let epoch = (orbit.epoch.get_day() - 2433281.5) + orbit.epoch.get_fraction();    // Days since 1950 Jan 0.0
+        let em = orbit.eccentricity;
+        let emsq = em * em;
+        let snodm = orbit.right_ascension.sin();
+        let cnodm = orbit.right_ascension.cos();
+        let sinomm = orbit.argument_of_perigee.sin();
+        let cosomm = orbit.argument_of_perigee.cos();
+        let sinim = orbit.inclination.sin();
+        let cosim = orbit.inclination.cos();
+        let inclm = orbit.inclination;
+
+        self.gsto = time::gmst_iau82(&orbit.epoch);
+
+        // Position of the moon at epoch (dscom)
+        let day = epoch + 18261.5;
+        let xnodce = (4.5236020 - 9.2422029e-4 * day) % (2.0 * PI);
+        let stem = xnodce.sin();
+        let ctem = xnodce.cos();
+        let zcosil = 0.91375164 - 0.03568096 * ctem;
+        let zsinil = (1.0 - zcosil * zcosil).sqrt();
+        let zsinhl = 0.089683511 * stem / zsinil;
+        let zcoshl = (1.0 - zsinhl * zsinhl).sqrt();
+        let gam = 5.8351514 + 0.0019443680 * day;
+        let zx = 0.39785416 * stem / zsinil;
+        let zy = zcoshl * ctem + 0.91744867 * zsinhl * stem;
+        let zx = gam + zx.atan2(zy) - xnodce;
+
+        let orientation = (cosim, sinim, cosomm, sinomm);
+        let xnoi = 1.0 / self.near.mean_motion;
+
+        let solar = ThirdBodyTerms::new(
+            (0.1945905, -0.98088458, 0.91744867, 0.39785416, cnodm, snodm),
+            orientation, em, 2.9864797e-6 * xnoi);
+
+        let lunar = ThirdBodyTerms::new(
+            (zx.cos(), zx.sin(), zcosil, zsinil, zcoshl * cnodm + zsinhl * snodm, snodm * zcoshl - cnodm * zsinhl),
+            orientation, em, 4.7968065e-7 * xnoi);
+
+        let zmol = (4.7199672 + 0.22997150 * day - gam) % (2.0 * PI);
+        let zmos = (6.2565837 + 0.017201977 * day) % (2.0 * PI);
+
+        self.solar = ThirdBodyPeriodics::new(&solar, zmos, ZNS, ZES, emsq);
+        self.lunar = ThirdBodyPeriodics::new(&lunar, zmol, ZNL, ZEL, emsq);
+
+        // Secular rates due to the sun and the moon (dsinit)
+        let (ses, sis, sls, sghs, shs) = solar.secular_rates(ZNS, emsq);
+        let (sel, sil, sll, sghl, shll) = lunar.secular_rates(ZNL, emsq);
+
+        // Avoid the singularity for nearly equatorial orbits
+        let equatorial = !(5.2359877e-2..=PI - 5.2359877e-2).contains(&inclm);
+        let mut shs = if equatorial { 0.0 } else { shs };
+        let shll = if equatorial { 0.0 } else { shll };
+
+        if sinim != 0.0 {
+            shs /= sinim;
+        }
+        let sgs = sghs - cosim * shs;
+
+        self.dedt = ses + sel;
+        self.didt = sis + sil;
+        self.dmdt = sls + sll;
+        self.domdt = sgs + sghl;
+        self.dnodt = shs;
+
+        if sinim != 0.0 {
+            self.domdt -= cosim / sinim * shll;
+            self.dnodt += shll / sinim;
+        }
+
+        self.initialize_resonance(em, emsq, sinim, cosim);
+    }
+
+    fn propagate(&mut self, delta_time: f64) -> Result<Teme, PropagationError>
+    {
+        let t = delta_time;
+        let mut secular = self.near.secular_update(t);
+
+        // Deep space secular effects and resonance
+        let em = self.near.orbit_0.eccentricity + self.dedt * t;
+        let inclm = self.near.orbit_0.inclination + self.didt * t;
+        secular.argument_of_perigee += self.domdt * t;
+        secular.right_ascension += self.dnodt * t;
+        secular.mean_anomaly += self.dmdt * t;
+
+        let nm = self.resonance_update(t, &mut secular);
+
+        let mut elements = self.near.mean_elements(&secular, nm, em, inclm)?;
+
+        // Lunar-solar periodics
+        self.apply_periodics(t, &mut elements);
+
+        if !(0.0..=1.0).contains(&elements.eccentricity) {
+            return Err(PropagationError::PerturbedEccentricity);
+        }
+
+        if elements.inclination < 0.0 {
+            elements.inclination = -elements.inclination;
+            elements.right_ascension += PI;
+            elements.argument_of_perigee -= PI;
+        }
+
+        let coefficients = PeriodicCoefficients::new(elements.inclination, &self.near.gravity);
+
+        self.near.periodics(&elements, &coefficients)
+    }
+}
+
+impl SDP4 {
+    pub fn new(orbit_0: Orbit) -> Self
+    {
+        Self::with_gravity_model(orbit_0, GravityModel::default())
+    }
+
+    pub fn with_gravity_model(orbit_0: Orbit, model: GravityModel) -> Self
+    {
+        SDP4 {
+            near:   SGP4::with_gravity_model(orbit_0, model),
+            gsto:   0.0,
+            solar:  ThirdBodyPeriodics::default(),
+            lunar:  ThirdBodyPeriodics::default(),
+            resonance: Resonance::default(),
+            dedt:   0.0,
+            didt:   0.0,
+            dmdt:   0.0,
+            domdt:  0.0,
+            dnodt:  0.0,
+        }
+    }
+
+    // Checks if the orbit needs the deep space perturbations, that is, if the period is of 225 minutes or more
+    pub fn is_deep_space(orbit: &Orbit, model: GravityModel) -> bool
+    {
+        let mut sgp4 = SGP4::with_gravity_model(orbit.clone(), model);
+        sgp4.recover_a02_n02();
+
+        sgp4.period() >= DEEP_SPACE_PERIOD
+    }
+
+    // Initializes the geopotential resonance terms for 12h and 24h orbits (dsinit)
+    fn initialize_resonance(&mut self, ecco: f64, eccsq: f64, sinim: f64, cosim: f64)
     {
-        let a1 = (KE/self.orbit_0.mean_motion).powf(2.0/3.0);
+        const Q22: f64 = 1.7891679e-6;
+        const Q31: f64 = 2.1460748e-6;
+        const Q33: f64 = 2.2123015e-7;
+        const ROOT22: f64 = 1.7891679e-6;
+        const ROOT44: f64 = 7.3636953e-9;
+        const ROOT54: f64 = 2.1765803e-9;
+        const ROOT32: f64 = 3.7393792e-7;
+        const ROOT52: f64 = 1.1428639e-7;
 
-        let d_aux = (3.0/2.0) * (K2 * (3.0*(self.orbit_0.inclination.cos()).powi(2) - 1.0))
-            / ((1.0 - self.orbit_0.eccentricity.powi(2)).powf(3.0/2.0));
+        let near = &self.near;
+        let orbit = &near.orbit_0;
+        let nm = near.mean_motion;
+        let res = &mut self.resonance;
 
-        let d1 = d_aux / (a1*a1);
+        res.kind = if (nm < 0.0052359877) && (nm > 0.0034906585) {
+            ResonanceKind::Synchronous
+        } else if (8.26e-3..=9.24e-3).contains(&nm) && ecco >= 0.5 {
+            ResonanceKind::HalfDay
+        } else {
+            ResonanceKind::None
+        };
 
-        let a0 = a1 * (1.0 - (1.0/3.0)*d1 - d1*d1 - (134.0/81.0)*d1.powi(3));
+        let theta = self.gsto % (2.0 * PI);
+        let aonv = (nm / near.gravity.xke).powf(X2O3);
 
-        let d0 = d_aux / (a0*a0);
+        match res.kind {
+            ResonanceKind::HalfDay => {
+                let cosisq = cosim * cosim;
+                let em = ecco;
+                let emsq = eccsq;
+                let eoc = em * emsq;
+                let g201 = -0.306 - (em - 0.64) * 0.440;
 
-        self.orbit_0.mean_motion = self.orbit_0.mean_motion / (1.0 + d0);
+                let (g211, g310, g322, g410, g422, g520) = if em <= 0.65 {
+                    (3.616 - 13.2470 * em + 16.2900 * emsq,
+                    -19.302 + 117.3900 * em - 228.4190 * emsq + 156.5910 * eoc,
+                    -18.9068 + 109.7927 * em - 214.6334 * emsq + 146.5816 * eoc,
+                    -41.122 + 242.6940 * em - 471.0940 * emsq + 313.9530 * eoc,
+                    -146.407 + 841.8800 * em - 1629.014 * emsq + 1083.4350 * eoc,
+                    -532.114 + 3017.977 * em - 5740.032 * emsq + 3708.2760 * eoc)
+                } else {
+                    (-72.099 + 331.819 * em - 508.738 * emsq + 266.724 * eoc,
+                    -346.844 + 1582.851 * em - 2415.925 * emsq + 1246.113 * eoc,
+                    -342.585 + 1554.908 * em - 2366.899 * emsq + 1215.972 * eoc,
+                    -1052.797 + 4758.686 * em - 7193.992 * emsq + 3651.957 * eoc,
+                    -3581.690 + 16178.110 * em - 24462.770 * emsq + 12422.520 * eoc,
+                    if em > 0.715 {
+                        -5149.66 + 29936.92 * em - 54087.36 * emsq + 31324.56 * eoc
+                    } else {
+                        1464.74 - 4664.75 * em + 3763.64 * emsq
+                    })
+                };
+
+                let (g533, g521, g532) = if em < 0.7 {
+                    (-919.22770 + 4988.6100 * em - 9064.7700 * emsq + 5542.21 * eoc,
+                    -822.71072 + 4568.6173 * em - 8491.4146 * emsq + 5337.524 * eoc,
+                    -853.66600 + 4690.2500 * em - 8624.7700 * emsq + 5341.4 * eoc)
+                } else {
+                    (-37995.780 + 161616.52 * em - 229838.20 * emsq + 109377.94 * eoc,
+                    -51752.104 + 218913.95 * em - 309468.16 * emsq + 146349.42 * eoc,
+                    -40023.880 + 170470.89 * em - 242699.48 * emsq + 115605.82 * eoc)
+                };
+
+                let sini2 = sinim * sinim;
+                let f220 = 0.75 * (1.0 + 2.0 * cosim + cosisq);
+                let f221 = 1.5 * sini2;
+                let f321 = 1.875 * sinim * (1.0 - 2.0 * cosim - 3.0 * cosisq);
+                let f322 = -1.875 * sinim * (1.0 + 2.0 * cosim - 3.0 * cosisq);
+                let f441 = 35.0 * sini2 * f220;
+                let f442 = 39.3750 * sini2 * sini2;
+                let f522 = 9.84375 * sinim * (sini2 * (1.0 - 2.0 * cosim - 5.0 * cosisq)
+                    + 0.33333333 * (-2.0 + 4.0 * cosim + 6.0 * cosisq));
+                let f523 = sinim * (4.92187512 * sini2 * (-2.0 - 4.0 * cosim + 10.0 * cosisq)
+                    + 6.56250012 * (1.0 + 2.0 * cosim - 3.0 * cosisq));
+                let f542 = 29.53125 * sinim * (2.0 - 8.0 * cosim + cosisq * (-12.0 + 8.0 * cosim + 10.0 * cosisq));
+                let f543 = 29.53125 * sinim * (-2.0 - 8.0 * cosim + cosisq * (12.0 + 8.0 * cosim - 10.0 * cosisq));
+
+                let xno2 = nm * nm;
+                let ainv2 = aonv * aonv;
+                let mut temp1 = 3.0 * xno2 * ainv2;
+                let mut temp = temp1 * ROOT22;
+                res.d2201 = temp * f220 * g201;
+                res.d2211 = temp * f221 * g211;
+                temp1 *= aonv;
+                temp = temp1 * ROOT32;
+                res.d3210 = temp * f321 * g310;
+                res.d3222 = temp * f322 * g322;
+                temp1 *= aonv;
+                temp = 2.0 * temp1 * ROOT44;
+                res.d4410 = temp * f441 * g410;
+                res.d4422 = temp * f442 * g422;
+                temp1 *= aonv;
+                temp = temp1 * ROOT52;
+                res.d5220 = temp * f522 * g520;
+                res.d5232 = temp * f523 * g532;
+                temp = 2.0 * temp1 * ROOT54;
+                res.d5421 = temp * f542 * g521;
+                res.d5433 = temp * f543 * g533;
+
+                res.xlamo = (orbit.mean_anomaly + orbit.right_ascension + orbit.right_ascension - theta - theta) % (2.0 * PI);
+                res.xfact = near.mdot + self.dmdt + 2.0 * (near.nodedot + self.dnodt - RPTIM) - nm;
+            },
+            ResonanceKind::Synchronous => {
+                let emsq = eccsq;
+                let g200 = 1.0 + emsq * (-2.5 + 0.8125 * emsq);
+                let g310 = 1.0 + 2.0 * emsq;
+                let g300 = 1.0 + emsq * (-6.0 + 6.60937 * emsq);
+                let f220 = 0.75 * (1.0 + cosim) * (1.0 + cosim);
+                let f311 = 0.9375 * sinim * sinim * (1.0 + 3.0 * cosim) - 0.75 * (1.0 + cosim);
+                let f330 = 1.875 * (1.0 + cosim).powi(3);
+
+                let del1 = 3.0 * nm * nm * aonv * aonv;
+                res.del2 = 2.0 * del1 * f220 * g200 * Q22;
+                res.del3 = 3.0 * del1 * f330 * g300 * Q33 * aonv;
+                res.del1 = del1 * f311 * g310 * Q31 * aonv;
+
+                res.xlamo = (orbit.mean_anomaly + orbit.right_ascension + orbit.argument_of_perigee - theta) % (2.0 * PI);
+                res.xfact = near.mdot + (near.argpdot + near.nodedot) - RPTIM + self.dmdt + self.domdt + self.dnodt - nm;
+            },
+            ResonanceKind::None => {}
+        }
 
-        self.semimayor_axis = a0 / (1.0 - d0);
+        // Initialize the integrator
+        res.xli = res.xlamo;
+        res.xni = nm;
+        res.atime = 0.0;
     }
 
+    // Integrates the resonance effects up to t minutes since epoch (dspace), returns the mean motion
+    fn resonance_update(&mut self, t: f64, secular: &mut SecularUpdate) -> f64
+    {
+        const FASX2: f64 = 0.13130908;
+        const FASX4: f64 = 2.8843198;
+        const FASX6: f64 = 0.37448087;
+        const G22: f64 = 5.7686396;
+        const G32: f64 = 0.95240898;
+        const G44: f64 = 1.8014998;
+        const G52: f64 = 1.0508330;
+        const G54: f64 = 4.4108898;
+        const STEPP: f64 = 720.0;
+        const STEPN: f64 = -720.0;
+        const STEP2: f64 = 259200.0;
+
+        let no = self.near.mean_motion;
+        let argpo = self.near.orbit_0.argument_of_perigee;
+        let argpdot = self.near.argpdot;
+        let res = &mut self.resonance;
+
+        if res.kind == ResonanceKind::None {
+            return no;
+        }
+
+        let theta = (self.gsto + t * RPTIM) % (2.0 * PI);
+
+        // Restart the integration from epoch if needed
+        if res.atime == 0.0 || t * res.atime <= 0.0 || t.abs() < res.atime.abs() {
+            res.atime = 0.0;
+            res.xni = no;
+            res.xli = res.xlamo;
+        }
+
+        let delt = if t > 0.0 { STEPP } else { STEPN };
+
+        let ft;
+        let mut xndt;
+        let mut xldot;
+        let mut xnddt;
+
+        loop {
+            // Dot terms calculated
+            if res.kind != ResonanceKind::HalfDay {
+                // Near-synchronous resonance terms
+                xndt = res.del1 * (res.xli - FASX2).sin() + res.del2 * (2.0 * (res.xli - FASX4)).sin()
+                    + res.del3 * (3.0 * (res.xli - FASX6)).sin();
+                xldot = res.xni + res.xfact;
+                xnddt = res.del1 * (res.xli - FASX2).cos() + 2.0 * res.del2 * (2.0 * (res.xli - FASX4)).cos()
+                    + 3.0 * res.del3 * (3.0 * (res.xli - FASX6)).cos();
+                xnddt *= xldot;
+            } else {
+                // Near-half-day resonance terms
+                let xomi = argpo + argpdot * res.atime;
+                let x2omi = xomi + xomi;
+                let x2li = res.xli + res.xli;
+                let xli = res.xli;
+
+                xndt = res.d2201 * (x2omi + xli - G22).sin() + res.d2211 * (xli - G22).sin()
+                    + res.d3210 * (xomi + xli - G32).sin() + res.d3222 * (-xomi + xli - G32).sin()
+                    + res.d4410 * (x2omi + x2li - G44).sin() + res.d4422 * (x2li - G44).sin()
+                    + res.d5220 * (xomi + xli - G52).sin() + res.d5232 * (-xomi + xli - G52).sin()
+                    + res.d5421 * (xomi + x2li - G54).sin() + res.d5433 * (-xomi + x2li - G54).sin();
+                xldot = res.xni + res.xfact;
+                xnddt = res.d2201 * (x2omi + xli - G22).cos() + res.d2211 * (xli - G22).cos()
+                    + res.d3210 * (xomi + xli - G32).cos() + res.d3222 * (-xomi + xli - G32).cos()
+                    + res.d5220 * (xomi + xli - G52).cos() + res.d5232 * (-xomi + xli - G52).cos()
+                    + 2.0 * (res.d4410 * (x2omi + x2li - G44).cos() + res.d4422 * (x2li - G44).cos()
+                    + res.d5421 * (xomi + x2li - G54).cos() + res.d5433 * (-xomi + x2li - G54).cos());
+                xnddt *= xldot;
+            }
+
+            // Integrator
+            if (t - res.atime).abs() < STEPP {
+                ft = t - res.atime;
+                break;
+            }
+
+            res.xli += xldot * delt + xndt * STEP2;
+            res.xni += xndt * delt + xnddt * STEP2;
+            res.atime += delt;
+        }
+
+        let nm = res.xni + xndt * ft + xnddt * ft * ft * 0.5;
+        let xl = res.xli + xldot * ft + xndt * ft * ft * 0.5;
+
+        secular.mean_anomaly = if res.kind != ResonanceKind::Synchronous {
+            xl - 2.0 * secular.right_ascension + 2.0 * theta
+        } else {
+            xl - secular.right_ascension - secular.argument_of_perigee + theta
+        };
+
+        nm
+    }
+
+    // Applies the lunar-solar long period periodics to the mean elements (dpper)
+    fn apply_periodics(&self, t: f64, elements: &mut MeanElements)
+    {
+        let (ses, sis, sls, sghs, shs) = self.solar.periodics(t);
+        let (sel, sil, sll, sghl, shll) = self.lunar.periodics(t);
+
+        let pe = ses + sel;
+        let pinc = sis + sil;
+        let pl = sls + sll;
+        let mut pgh = sghs + sghl;
+        let mut ph = shs + shll;
+
+        elements.inclination += pinc;
+        elements.eccentricity += pe;
+
+        let sinip = elements.inclination.sin();
+        let cosip = elements.inclination.cos();
+
+        if elements.inclination >= 0.2 {
+            // Apply periodics directly
+            ph /= sinip;
+            pgh -= cosip * ph;
+            elements.argument_of_perigee += pgh;
+            elements.right_ascension += ph;
+            elements.mean_anomaly += pl;
+        } else {
+            // Apply periodics with Lyddane modification
+            let sinop = elements.right_ascension.sin();
+            let cosop = elements.right_ascension.cos();
+            let alfdp = sinip * sinop + (ph * cosop + pinc * cosip * sinop);
+            let betdp = sinip * cosop + (-ph * sinop + pinc * cosip * cosop);
+
+            let nodep = elements.right_ascension % (2.0 * PI);
+            let xls = elements.mean_anomaly + elements.argument_of_perigee + cosip * nodep
+                + pl + pgh - pinc * nodep * sinip;
+
+            let xnoh = nodep;
+            let mut nodep = alfdp.atan2(betdp);
+
+            if (xnoh - nodep).abs() > PI {
+                if nodep < xnoh {
+                    nodep += 2.0 * PI;
+                } else {
+                    nodep -= 2.0 * PI;
+                }
+            }
+
+            elements.mean_anomaly += pl;
+            elements.right_ascension = nodep;
+            elements.argument_of_perigee = xls - elements.mean_anomaly - cosip * nodep;
+        }
+    }
+}
+
+impl PeriodicCoefficients {
+    fn new(inclination: f64, gravity: &GravityConstants) -> Self
+    {
+        let sinio = inclination.sin();
+        let cosio = inclination.cos();
+        let cosio2 = cosio * cosio;
+
+        // Avoid dividing by zero when the inclination is 180 deg
+        let divisor = if (cosio + 1.0).abs() > TEMP4 { 1.0 + cosio } else { TEMP4 };
+
+        PeriodicCoefficients {
+            sinio,
+            cosio,
+            con41:  3.0 * cosio2 - 1.0,
+            x1mth2: 1.0 - cosio2,
+            x7thm1: 7.0 * cosio2 - 1.0,
+            xlcof:  -0.25 * gravity.j3oj2 * sinio * (3.0 + 5.0 * cosio) / divisor,
+            aycof:  -0.5 * gravity.j3oj2 * sinio,
+        }
+    }
+}
+
+impl ThirdBodyTerms {
+    /*
+     * The body is given by the orientation (cos g, sin g, cos i, sin i, cos h, sin h) of its orbit and
+     * the orbit of the satellite by (cos i, sin i, cos w, sin w) with its eccentricity.
+    */
+    fn new(body: (f64, f64, f64, f64, f64, f64), orbit: (f64, f64, f64, f64), em: f64, s3: f64) -> Self
+    {
+        let (zcosg, zsing, zcosi, zsini, zcosh, zsinh) = body;
+        let (cosim, sinim, cosomm, sinomm) = orbit;
+
+        let emsq = em * em;
+        let betasq = 1.0 - emsq;
+        let rtemsq = betasq.sqrt();
+
+        let a1 = zcosg * zcosh + zsing * zcosi * zsinh;
+        let a3 = -zsing * zcosh + zcosg * zcosi * zsinh;
+        let a7 = -zcosg * zsinh + zsing * zcosi * zcosh;
+        let a8 = zsing * zsini;
+        let a9 = zsing * zsinh + zcosg * zcosi * zcosh;
+        let a10 = zcosg * zsini;
+        let a2 = cosim * a7 + sinim * a8;
+        let a4 = cosim * a9 + sinim * a10;
+        let a5 = -sinim * a7 + cosim * a8;
+        let a6 = -sinim * a9 + cosim * a10;
+
+        let x1 = a1 * cosomm + a2 * sinomm;
+        let x2 = a3 * cosomm + a4 * sinomm;
+        let x3 = -a1 * sinomm + a2 * cosomm;
+        let x4 = -a3 * sinomm + a4 * cosomm;
+        let x5 = a5 * sinomm;
+        let x6 = a6 * sinomm;
+        let x7 = a5 * cosomm;
+        let x8 = a6 * cosomm;
+
+        let z31 = 12.0 * x1 * x1 - 3.0 * x3 * x3;
+        let z32 = 24.0 * x1 * x2 - 6.0 * x3 * x4;
+        let z33 = 12.0 * x2 * x2 - 3.0 * x4 * x4;
+        let z1 = 3.0 * (a1 * a1 + a2 * a2) + z31 * emsq;
+        let z2 = 6.0 * (a1 * a3 + a2 * a4) + z32 * emsq;
+        let z3 = 3.0 * (a3 * a3 + a4 * a4) + z33 * emsq;
+        let s4 = s3 * rtemsq;
+
+        ThirdBodyTerms {
+            s1: -15.0 * em * s4,
+            s2: -0.5 * s3 / rtemsq,
+            s3,
+            s4,
+            s5: x1 * x3 + x2 * x4,
+            s6: x2 * x3 + x1 * x4,
+            s7: x2 * x4 - x1 * x3,
+            z1: z1 + z1 + betasq * z31,
+            z2: z2 + z2 + betasq * z32,
+            z3: z3 + z3 + betasq * z33,
+            z11: -6.0 * a1 * a5 + emsq * (-24.0 * x1 * x7 - 6.0 * x3 * x5),
+            z12: -6.0 * (a1 * a6 + a3 * a5) + emsq * (-24.0 * (x2 * x7 + x1 * x8) - 6.0 * (x3 * x6 + x4 * x5)),
+            z13: -6.0 * a3 * a6 + emsq * (-24.0 * x2 * x8 - 6.0 * x4 * x6),
+            z21: 6.0 * a2 * a5 + emsq * (24.0 * x1 * x5 - 6.0 * x3 * x7),
+            z22: 6.0 * (a4 * a5 + a2 * a6) + emsq * (24.0 * (x2 * x5 + x1 * x6) - 6.0 * (x4 * x7 + x3 * x8)),
+            z23: 6.0 * a4 * a6 + emsq * (24.0 * x2 * x6 - 6.0 * x4 * x8),
+            z31,
+            z32,
+            z33,
+        }
+    }
+
+    // Secular rates of (e, i, M, w, node) due to the body, zn being its mean motion
+    fn secular_rates(&self, zn: f64, emsq: f64) -> (f64, f64, f64, f64, f64)
+    {
+        (
+            self.s1 * zn * self.s5,
+            self.s2 * zn * (self.z11 + self.z13),
+            -zn * self.s3 * (self.z1 + self.z3 - 14.0 - 6.0 * emsq),
+            self.s4 * zn * (self.z31 + self.z33 - 6.0),
+            -zn * self.s2 * (self.z21 + self.z23),
+        )
+    }
+}
+
+impl ThirdBodyPeriodics {
+    fn new(terms: &ThirdBodyTerms, zmo: f64, zn: f64, ze: f64, emsq: f64) -> Self
+    {
+        ThirdBodyPeriodics {
+            zmo,
+            zn,
+            ze,
+            e2:  2.0 * terms.s1 * terms.s6,
+            e3:  2.0 * terms.s1 * terms.s7,
+            i2:  2.0 * terms.s2 * terms.z12,
+            i3:  2.0 * terms.s2 * (terms.z13 - terms.z11),
+            l2:  -2.0 * terms.s3 * terms.z2,
+            l3:  -2.0 * terms.s3 * (terms.z3 - terms.z1),
+            l4:  -2.0 * terms.s3 * (-21.0 - 9.0 * emsq) * ze,
+            gh2: 2.0 * terms.s4 * terms.z32,
+            gh3: 2.0 * terms.s4 * (terms.z33 - terms.z31),
+            gh4: -18.0 * terms.s4 * ze,
+            h2:  -2.0 * terms.s2 * terms.z22,
+            h3:  -2.0 * terms.s2 * (terms.z23 - terms.z21),
+        }
+    }
+
+    // Periodic variations of (e, i, M, w, node) at t minutes since epoch
+    fn periodics(&self, t: f64) -> (f64, f64, f64, f64, f64)
+    {
+        let zm = self.zmo + self.zn * t;
+        let zf = zm + 2.0 * self.ze * zm.sin();
+        let sinzf = zf.sin();
+        let f2 = 0.5 * sinzf * sinzf - 0.25;
+        let f3 = -0.5 * sinzf * zf.cos();
+
+        (
+            self.e2 * f2 + self.e3 * f3,
+            self.i2 * f2 + self.i3 * f3,
+            self.l2 * f2 + self.l3 * f3 + self.l4 * sinzf,
+            self.gh2 * f2 + self.gh3 * f3 + self.gh4 * sinzf,
+            self.h2 * f2 + self.h3 * f3,
+        )
+    }
+}
+
+impl Default for ThirdBodyPeriodics {
+    fn default() -> Self
+    {
+        ThirdBodyPeriodics {
+            zmo: 0.0, zn: 0.0, ze: 0.0,
+            e2: 0.0, e3: 0.0,
+            i2: 0.0, i3: 0.0,
+            l2: 0.0, l3: 0.0, l4: 0.0,
+            gh2: 0.0, gh3: 0.0, gh4: 0.0,
+            h2: 0.0, h3: 0.0,
+        }
+    }
+}
+
+impl Default for Resonance {
+    fn default() -> Self
+    {
+        Resonance {
+            kind:  ResonanceKind::None,
+            d2201: 0.0, d2211: 0.0, d3210: 0.0, d3222: 0.0, d4410: 0.0,
+            d4422: 0.0, d5220: 0.0, d5232: 0.0, d5421: 0.0, d5433: 0.0,
+            del1:  0.0, del2: 0.0, del3: 0.0,
+            xfact: 0.0,
+            xlamo: 0.0,
+            atime: 0.0,
+            xli:   0.0,
+            xni:   0.0,
+        }
+    }
 }
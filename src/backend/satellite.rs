@@ -1,112 +1,144 @@
 
+use super::coordinates::{Ecef, Geodetic, Teme};
+use super::ellipsoid::Ellipsoid;
+use super::frames::EarthOrientation;
+use super::gravity::GravityModel;
 use super::orbit::Orbit;
+use super::timescale::TimeScales;
 use super::tle::TLE;
-use super::propagator::{Propagate, SGP4};
+use super::propagator::{Propagate, PropagationError, select_propagator};
 use super::vector::PositionVector;
 
-use chrono::{Utc, TimeZone, NaiveDate, NaiveTime, Timelike};
-use julian::Calendar;
+use chrono::{DateTime, TimeDelta, Utc};
+use std::rc::Rc;
 
 pub struct Satellite 
 {
     propagator: Box<dyn Propagate>,
     tle: TLE,
+    time_scales: Rc<TimeScales>,
+    ellipsoid: Ellipsoid,
     points: Vec<(f64, f64)>,
-    coords_eci: PositionVector,
-    gst: f64
+    eci: Teme,
+    ecef: Ecef,
+    error: Option<PropagationError>
 }  
 
 impl Satellite
 {
-    pub fn new(tle_path: &str) -> Result<Self, &str>
+    pub fn new(tle: TLE) -> Self
     {
-        let tle = TLE::new(tle_path)?;
         let orbit = Orbit::new(&tle);
-        let mut propagator = SGP4::new(orbit);
+        let mut propagator = select_propagator(orbit, GravityModel::default());
 
         propagator.initialize();
 
-        Ok(Satellite
+        Satellite
         {
-            propagator: Box::new(propagator),
+            propagator: propagator,
             tle: tle,
+            time_scales: Rc::new(TimeScales::default()),
+            ellipsoid: Ellipsoid::default(),
             points: Vec::new(),
-            coords_eci: PositionVector::new(0.0, 0.0, 0.0),
-            gst: 0.0
-        })
+            eci: Teme::new(PositionVector::new(0.0, 0.0, 0.0), PositionVector::new(0.0, 0.0, 0.0)),
+            ecef: Ecef::new(PositionVector::new(0.0, 0.0, 0.0), PositionVector::new(0.0, 0.0, 0.0)),
+            error: None
+        }
     }
 
-    pub fn print(&self)
+    // Leap seconds and Earth orientation used to place the satellite over the earth
+    pub fn set_time_scales(&mut self, time_scales: Rc<TimeScales>)
     {
-        self.tle.print_data();
+        self.time_scales = time_scales;
     }
 
-    pub fn get_points(&self) -> &Vec<(f64, f64)>
+    // Datum of the geodetic positions and the ground track
+    pub fn set_ellipsoid(&mut self, ellipsoid: Ellipsoid)
     {
-        return &self.points;
+        self.ellipsoid = ellipsoid;
     }
 
-    pub fn get_julian_day() -> f64
+    pub fn get_ellipsoid(&self) -> Ellipsoid
     {
-        let cal = Calendar::GREGORIAN;
-        let date = cal.now().unwrap();
-
-        let jd0 = date.0.julian_day_number() as f64 - 0.5;
+        return self.ellipsoid;
+    }
 
-        let now = Utc::now();
-        let days_since_jd0 = now.num_seconds_from_midnight() as f64 / (3600.0*24.0);
+    pub fn print(&self)
+    {
+        self.tle.print_data();
+    }
 
-        return jd0 as f64 + days_since_jd0;
+    pub fn get_points(&self) -> &Vec<(f64, f64)>
+    {
+        return &self.points;
     }
 
     pub fn get_trajectory(&mut self)
     {
-        self.points.clear();
-        
-        for i in -60..60
-        {
-            self.gst = self.get_gst(Self::get_julian_day() + (i as f64 / (60.0*24.0)));
-            self.coords_eci = self.propagator.propagate(self.time_since_epoch_in_minutes() + i as f64);
-            self.points.push((self.get_geodetic_position().get_y() * (180.0/core::f64::consts::PI), self.get_geodetic_position().get_x() * (180.0/core::f64::consts::PI)));
-        }
+        self.get_trajectory_at(Utc::now());
     }
 
-    fn get_gst(&self, julian_time: f64) -> f64
+    // Ground track from an hour before to an hour after the given instant
+    pub fn get_trajectory_at(&mut self, time: DateTime<Utc>)
     {
-        let j2000 = 2451545.0;  // Epoch of reference
+        self.points.clear();
 
-        let jd0 = julian_time.floor() + 0.5;
+        let minutes = self.minutes_since_epoch(time);
 
-        let days_since_jd0 = julian_time - jd0;
-        let hours_since_jd0 = days_since_jd0 * 24.0;  // H
+        for i in -60..60
+        {
+            // Minutes past the dates chrono can represent are left out
+            let Some(point_time) = time.checked_add_signed(TimeDelta::minutes(i)) else {
+                continue;
+            };
 
-        let days_since_j2000  = julian_time - j2000;    // D
-        let centuries_since_j2000  = days_since_j2000  / 36525.0;   // T
-        let whole_days_since_j2000  = jd0 - j2000;
+            let orientation = EarthOrientation::new(point_time, &self.time_scales);
 
-        let gmst = 6.697374558 + 0.06570982441908*whole_days_since_j2000  + 1.00273790935*hours_since_jd0 + 0.000026*centuries_since_j2000.powi(2);
+            // Points where the propagation fails are left out of the trajectory
+            if let Ok(state) = self.propagator.propagate(minutes + i as f64) {
+                let geodetic = orientation.teme_to_itrf(&state).to_geodetic(&self.ellipsoid);
 
-        let gmst_normalized = gmst.rem_euclid(24.0);
+                // Map points are (x, y), the longitude goes first
+                self.points.push((geodetic.get_longitude_degrees(), geodetic.get_latitude_degrees()));
+            }
+        }
+    }
 
-        let gmst_rads = gmst_normalized * (core::f64::consts::PI/12.0);
+    pub fn get_eci_position(&self) -> &PositionVector
+    {
+        return &self.eci.position;
+    }
 
-        return gmst_rads;
+    pub fn get_eci_velocity(&self) -> &PositionVector
+    {
+        return &self.eci.velocity;
     }
 
-    pub fn get_eci_position(&self) -> &PositionVector
+    pub fn get_eci_state(&self) -> &Teme
     {
-        return &self.coords_eci;
+        return &self.eci;
     }
 
-    pub fn get_geodetic_position(&self) -> PositionVector // (latitude, longitude, altitude)
+    // Earth fixed (ITRF) position, the PEF one when there is no polar motion data
+    pub fn get_ecef_position(&self) -> &PositionVector
     {
-        let mut pos_geodetic = self.coords_eci.ecef_to_geodetic();
+        return &self.ecef.position;
+    }
 
-        // Convert to real geodetic(ecef)
-        pos_geodetic.set_y((pos_geodetic.get_y() - self.gst + core::f64::consts::PI).rem_euclid(2.0*core::f64::consts::PI) - core::f64::consts::PI);
+    pub fn get_ecef_velocity(&self) -> &PositionVector
+    {
+        return &self.ecef.velocity;
+    }
 
-        // TODO: Look for better way to do this
-        pos_geodetic.clone()
+    pub fn get_ecef_state(&self) -> &Ecef
+    {
+        return &self.ecef;
+    }
+
+    // Latitude, longitude and altitude over the datum of the satellite
+    pub fn get_geodetic_position(&self) -> Geodetic
+    {
+        return self.ecef.to_geodetic(&self.ellipsoid);
     }
 
     pub fn get_tle(&self) -> &TLE
@@ -114,39 +146,61 @@ impl Satellite
         return &self.tle;
     }
 
-    pub fn update_position(&mut self)
+    pub fn get_name(&self) -> String
     {
-        self.gst = self.get_gst(Self::get_julian_day());
+        return self.tle.get_display_name();
+    }
 
-        self.coords_eci = self.propagator.propagate(self.time_since_epoch_in_minutes());
+    // Error of the last position update, if any
+    pub fn get_error(&self) -> Option<PropagationError>
+    {
+        return self.error;
     }
 
-    // Function to calculate the time difference between two NaiveDateTime in minutes
-    pub fn time_since_epoch_in_minutes(&self) -> f64 {
+    pub fn update_position(&mut self) -> Result<(), PropagationError>
+    {
+        self.update_position_at(Utc::now())
+    }
+
+    // Moves the satellite to where it is at the given instant
+    pub fn update_position_at(&mut self, time: DateTime<Utc>) -> Result<(), PropagationError>
+    {
+        let result = self.propagate_at(time);
 
-        let epoch_year = self.tle.epoch_year; 
-        let epoch_day = self.tle.epoch_day;
+        self.error = result.as_ref().err().copied();
+
+        let state = result?;
+        self.ecef = EarthOrientation::new(time, &self.time_scales).teme_to_itrf(&state);
+        self.eci = state;
+
+        Ok(())
+    }
 
-        let day_of_year = epoch_day as u32;
+    // TEME state at the given instant, the position of the satellite is left as it is
+    pub fn propagate_at(&mut self, time: DateTime<Utc>) -> Result<Teme, PropagationError>
+    {
+        let minutes = self.minutes_since_epoch(time);
 
-        // Convert the day of the year to a NaiveDate
-        let tle_date = NaiveDate::from_yo_opt(epoch_year, day_of_year);
+        self.propagate_minutes(minutes)
+    }
 
-        // Calculate the time from the fractional day part (fraction of 24 hours)
-        let seconds_in_day = 86400.0 * (epoch_day - day_of_year as f64);
-        let tle_time = NaiveTime::from_num_seconds_from_midnight_opt(seconds_in_day as u32, 0);
+    // TEME state at the given minutes from the epoch of the TLE, negative for times before it
+    pub fn propagate_minutes(&mut self, minutes: f64) -> Result<Teme, PropagationError>
+    {
+        self.propagator.propagate(minutes)
+    }
 
-        // Create a full TLE epoch DateTime in UTC
-        let tle_datetime = Utc
-            .from_utc_datetime(&NaiveDate::and_time(&tle_date.unwrap(), tle_time.unwrap()))
-            .with_timezone(&Utc);
+    // Minutes from the epoch of the TLE to now, at microsecond precision
+    pub fn time_since_epoch_in_minutes(&self) -> f64 {
+        self.minutes_since_epoch(Utc::now())
+    }
 
-        // Get the current time in UTC
-        let now = Utc::now();
+    // Minutes from the epoch of the TLE to the given instant, at microsecond precision
+    pub fn minutes_since_epoch(&self, time: DateTime<Utc>) -> f64 {
 
-        // Calculate the delta in minutes
-        let delta = now.signed_duration_since(tle_datetime).num_seconds() as f64 / 60.0;
+        let elapsed = time.signed_duration_since(self.tle.get_epoch());
 
-        delta
+        // Whole seconds and the rest apart so long spans don't overflow the microseconds
+        elapsed.num_seconds() as f64 / 60.0 + elapsed.subsec_nanos() as f64 / 60.0e9
     }
 }
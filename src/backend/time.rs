@@ -0,0 +1,149 @@
+use chrono::{DateTime, Datelike, NaiveDate, TimeDelta, Timelike, Utc};
+
+use core::f64::consts::PI;
+
+// Julian date of J2000.0, 2000-01-01 at 12h
+pub const J2000: f64 = 2451545.0;
+
+// Julian date of the start of the modified Julian date, 1858-11-17 at 0h
+pub const MJD_ZERO: f64 = 2400000.5;
+
+pub const DAYS_PER_CENTURY: f64 = 36525.0;
+
+const SECONDS_PER_DAY: f64 = 86400.0;
+
+// Julian date of 0001-01-01 at 0h, first day counted by chrono
+const JD_COMMON_ERA: f64 = 1721425.5;
+
+// Julian date split in the date at 0h and the fraction of the day, a single f64 only keeps ~20 µs
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct JulianDate {
+    day: f64,
+    fraction: f64
+}
+
+impl JulianDate
+{
+    pub fn new(day: f64, fraction: f64) -> Self
+    {
+        JulianDate {
+            day: day,
+            fraction: fraction
+        }
+    }
+
+    // Julian date of the given instant, the scale of the result is the one of the instant (UTC for a DateTime<Utc>)
+    pub fn from_datetime(time: DateTime<Utc>) -> Self
+    {
+        let day = JD_COMMON_ERA + (time.num_days_from_ce() - 1) as f64;
+        let seconds = time.num_seconds_from_midnight() as f64 + time.nanosecond() as f64 / 1.0e9;
+
+        Self::new(day, seconds / SECONDS_PER_DAY)
+    }
+
+    // Julian date of the given day of the year, being 1.0 the start of January 1st. None for years out of the range of chrono
+    pub fn from_day_of_year(year: i32, day_of_year: f64) -> Option<Self>
+    {
+        let day = day_of_year.floor();
+
+        // Days of the year before it, from January 1st at 0h
+        let jan_1 = NaiveDate::from_yo_opt(year, 1)?.num_days_from_ce() - 1;
+
+        Some(Self::new(JD_COMMON_ERA + jan_1 as f64 + day - 1.0, day_of_year - day))
+    }
+
+    pub fn from_mjd(mjd: f64) -> Self
+    {
+        let day = mjd.floor();
+
+        Self::new(MJD_ZERO + day, mjd - day)
+    }
+
+    // Instant of the Julian date, rounded to the microsecond
+    pub fn to_datetime(&self) -> Option<DateTime<Utc>>
+    {
+        let whole = (self.day - 0.5).floor();
+        let fraction = (self.day - 0.5 - whole) + self.fraction;
+
+        let days = (whole + 0.5 - JD_COMMON_ERA) as i64 + 1;
+        let microseconds = (fraction * SECONDS_PER_DAY * 1.0e6).round() as i64;
+
+        let midnight = NaiveDate::from_num_days_from_ce_opt(i32::try_from(days).ok()?)?.and_hms_opt(0, 0, 0)?;
+
+        Some((midnight + TimeDelta::microseconds(microseconds)).and_utc())
+    }
+
+    // Same date moved by the given days, only the fraction changes
+    pub fn add_days(&self, days: f64) -> Self
+    {
+        Self::new(self.day, self.fraction + days)
+    }
+
+    pub fn get_day(&self) -> f64
+    {
+        return self.day;
+    }
+
+    pub fn get_fraction(&self) -> f64
+    {
+        return self.fraction;
+    }
+
+    // Julian date as a single number
+    pub fn value(&self) -> f64
+    {
+        return self.day + self.fraction;
+    }
+
+    // Modified Julian date
+    pub fn mjd(&self) -> f64
+    {
+        return (self.day - MJD_ZERO) + self.fraction;
+    }
+
+    // Days since J2000.0, keeping the precision of the fraction
+    pub fn days_since_j2000(&self) -> f64
+    {
+        return (self.day - J2000) + self.fraction;
+    }
+
+    // Julian centuries since J2000.0
+    pub fn centuries_since_j2000(&self) -> f64
+    {
+        return self.days_since_j2000() / DAYS_PER_CENTURY;
+    }
+}
+
+// Greenwich mean sidereal time in rad (IAU-82), the one SGP4 and the TEME frame are defined with
+pub fn gmst_iau82(ut1: &JulianDate) -> f64
+{
+    let tut1 = ut1.centuries_since_j2000();
+
+    let seconds = -6.2e-6 * tut1.powi(3) + 0.093104 * tut1 * tut1
+        + (876600.0 * 3600.0 + 8640184.812866) * tut1 + 67310.54841;
+
+    // 360 deg / 86400 s = 1/240
+    (seconds * (PI / 180.0) / 240.0).rem_euclid(2.0 * PI)
+}
+
+// Earth rotation angle in rad (IAU-2000)
+pub fn earth_rotation_angle(ut1: &JulianDate) -> f64
+{
+    let days = ut1.days_since_j2000();
+
+    // Fractions of the day apart, to keep the precision of the whole turns
+    let turns = ut1.day.rem_euclid(1.0) + ut1.fraction.rem_euclid(1.0);
+
+    (2.0 * PI * (turns + 0.7790572732640 + 0.00273781191135448 * days)).rem_euclid(2.0 * PI)
+}
+
+// Greenwich mean sidereal time in rad (IAU-2006), takes UT1 for the rotation and TT for the precession
+pub fn gmst_iau2006(ut1: &JulianDate, tt: &JulianDate) -> f64
+{
+    let t = tt.centuries_since_j2000();
+
+    // Accumulated precession in right ascension, in arcseconds
+    let precession = 0.014506 + (4612.156534 + (1.3915817 + (-0.00000044 + (-0.000029956 + -0.0000000368 * t) * t) * t) * t) * t;
+
+    (earth_rotation_angle(ut1) + (precession / 3600.0).to_radians()).rem_euclid(2.0 * PI)
+}
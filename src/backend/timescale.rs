@@ -0,0 +1,404 @@
+use super::time::JulianDate;
+
+use chrono::{DateTime, Utc};
+use std::fmt;
+use std::fs;
+use std::io;
+
+// TT runs 32.184 s ahead of TAI, GPS time 19 s behind it
+const TT_MINUS_TAI: f64 = 32.184;
+const TAI_MINUS_GPS: f64 = 19.0;
+
+const SECONDS_PER_DAY: f64 = 86400.0;
+
+// Modified Julian date of 1900-01-01, origin of the NTP seconds of leap-seconds.list
+const MJD_NTP_EPOCH: f64 = 15020.0;
+
+const ARCSECONDS_TO_RAD: f64 = core::f64::consts::PI / (180.0 * 3600.0);
+
+// Leap seconds up to 2017-01-01 as (MJD when they start, TAI-UTC in s)
+const BUNDLED_LEAP_SECONDS: [(f64, f64); 28] = [
+    (41317.0, 10.0), (41499.0, 11.0), (41683.0, 12.0), (42048.0, 13.0), (42413.0, 14.0), (42778.0, 15.0), (43144.0, 16.0),
+    (43509.0, 17.0), (43874.0, 18.0), (44239.0, 19.0), (44786.0, 20.0), (45151.0, 21.0), (45516.0, 22.0), (46247.0, 23.0),
+    (47161.0, 24.0), (47892.0, 25.0), (48257.0, 26.0), (48804.0, 27.0), (49169.0, 28.0), (49534.0, 29.0), (50083.0, 30.0),
+    (50630.0, 31.0), (51179.0, 32.0), (53736.0, 33.0), (54832.0, 34.0), (56109.0, 35.0), (57204.0, 36.0), (57754.0, 37.0),
+];
+
+// Failure to read a leap second or Earth orientation file
+#[derive(Debug)]
+pub enum TimeDataError {
+    Io(io::Error),
+    LeapSecond { line: usize },
+    Eop { line: usize },
+    Empty,
+}
+
+impl fmt::Display for TimeDataError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TimeDataError::Io(error) => write!(f, "I/O error: {}", error),
+            TimeDataError::LeapSecond { line } => write!(f, "line {}: invalid leap second entry", line),
+            TimeDataError::Eop { line } => write!(f, "line {}: invalid Earth orientation entry", line),
+            TimeDataError::Empty => write!(f, "no entries found"),
+        }
+    }
+}
+
+impl std::error::Error for TimeDataError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            TimeDataError::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for TimeDataError {
+    fn from(error: io::Error) -> Self {
+        TimeDataError::Io(error)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TimeScale {
+    UTC,
+    TAI,
+    TT,
+    GPS,
+    UT1
+}
+
+impl TimeScale {
+    pub fn name(&self) -> &'static str {
+        match self {
+            TimeScale::UTC => "UTC",
+            TimeScale::TAI => "TAI",
+            TimeScale::TT => "TT",
+            TimeScale::GPS => "GPS",
+            TimeScale::UT1 => "UT1",
+        }
+    }
+}
+
+// TAI-UTC along time, one entry per leap second
+#[derive(Clone, Debug)]
+pub struct LeapSeconds {
+    entries: Vec<(f64, f64)>
+}
+
+impl LeapSeconds
+{
+    // Table shipped with the program, valid until the next leap second is announced
+    pub fn bundled() -> Self
+    {
+        LeapSeconds {
+            entries: BUNDLED_LEAP_SECONDS.to_vec()
+        }
+    }
+
+    pub fn new(file_path: &str) -> Result<Self, TimeDataError>
+    {
+        let contents = fs::read_to_string(file_path)?;
+
+        Self::parse(&contents)
+    }
+
+    // Reads the IERS Leap_Second.dat (MJD, day, month, year, TAI-UTC) or leap-seconds.list (NTP seconds, TAI-UTC)
+    pub fn parse(contents: &str) -> Result<Self, TimeDataError>
+    {
+        let mut entries = Vec::new();
+
+        for (i, line) in contents.lines().enumerate() {
+            // Comments start with '#', leap-seconds.list also adds them after the values
+            let values: Vec<&str> = line.split('#').next().unwrap_or("").split_whitespace().collect();
+
+            if values.is_empty() {
+                continue;
+            }
+
+            let numbers: Vec<f64> = values
+                .iter()
+                .map(|value| value.parse::<f64>())
+                .collect::<Result<_, _>>()
+                .map_err(|_| TimeDataError::LeapSecond { line: i + 1 })?;
+
+            let entry = match numbers[..] {
+                [mjd, _, _, _, offset] => (mjd, offset),
+                [ntp, offset] => (MJD_NTP_EPOCH + ntp / SECONDS_PER_DAY, offset),
+                _ => return Err(TimeDataError::LeapSecond { line: i + 1 }),
+            };
+
+            entries.push(entry);
+        }
+
+        if entries.is_empty() {
+            return Err(TimeDataError::Empty);
+        }
+
+        entries.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        Ok(LeapSeconds {
+            entries: entries
+        })
+    }
+
+    // TAI-UTC in s at the given UTC instant, before 1972 the first value is kept
+    pub fn tai_minus_utc(&self, utc: &JulianDate) -> f64
+    {
+        let mjd = utc.mjd();
+
+        return self.entries
+            .iter()
+            .rev()
+            .find(|(start, _)| mjd >= *start)
+            .or(self.entries.first())
+            .map_or(0.0, |(_, offset)| *offset);
+    }
+
+    pub fn get_entries(&self) -> &Vec<(f64, f64)>
+    {
+        return &self.entries;
+    }
+}
+
+impl Default for LeapSeconds {
+    fn default() -> Self {
+        Self::bundled()
+    }
+}
+
+// Earth orientation of a day, as published by the IERS
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct EopEntry {
+    /// Modified Julian date of the day at 0h UTC
+    pub mjd: f64,
+
+    /// Polar motion in rad
+    pub x_pole: f64,
+    pub y_pole: f64,
+
+    /// UT1-UTC in s
+    pub ut1_minus_utc: f64
+}
+
+// Daily Earth orientation parameters, interpolated between days
+#[derive(Clone, Debug)]
+pub struct EopTable {
+    entries: Vec<EopEntry>
+}
+
+impl EopTable
+{
+    pub fn new(file_path: &str) -> Result<Self, TimeDataError>
+    {
+        let contents = fs::read_to_string(file_path)?;
+
+        Self::parse(&contents)
+    }
+
+    // Reads the IERS finals2000A files (finals2000A.all, .data, .daily) or the CelesTrak EOP files (EOP-All.txt)
+    pub fn parse(contents: &str) -> Result<Self, TimeDataError>
+    {
+        let is_celestrak = contents.lines().any(|line| line.trim_start().starts_with("BEGIN OBSERVED"));
+
+        let mut entries = Vec::new();
+
+        for (i, line) in contents.lines().enumerate() {
+            let entry = if is_celestrak {
+                Self::parse_celestrak_line(line)
+            } else {
+                Self::parse_finals_line(line)
+            };
+
+            match entry {
+                Ok(Some(entry)) => entries.push(entry),
+                Ok(None) => {},
+                Err(()) => return Err(TimeDataError::Eop { line: i + 1 }),
+            }
+        }
+
+        if entries.is_empty() {
+            return Err(TimeDataError::Empty);
+        }
+
+        entries.sort_by(|a, b| a.mjd.total_cmp(&b.mjd));
+
+        Ok(EopTable {
+            entries: entries
+        })
+    }
+
+    // Fixed columns of finals2000A, the days still without UT1-UTC (at the end of the file) are skipped
+    fn parse_finals_line(line: &str) -> Result<Option<EopEntry>, ()>
+    {
+        if line.trim().is_empty() {
+            return Ok(None);
+        }
+
+        // Columns counting from 1 as in readme.finals2000A
+        let column = |start: usize, end: usize| line.get(start - 1..end.min(line.len())).unwrap_or("").trim();
+
+        let ut1_minus_utc = column(59, 68);
+
+        if ut1_minus_utc.is_empty() {
+            return Ok(None);
+        }
+
+        let number = |value: &str| value.parse::<f64>().map_err(|_| ());
+
+        Ok(Some(EopEntry {
+            mjd: number(column(8, 15))?,
+            x_pole: number(column(19, 27))? * ARCSECONDS_TO_RAD,
+            y_pole: number(column(38, 46))? * ARCSECONDS_TO_RAD,
+            ut1_minus_utc: number(ut1_minus_utc)?
+        }))
+    }
+
+    // Year, month, day, MJD, x, y, UT1-UTC, ... inside the OBSERVED and PREDICTED blocks
+    fn parse_celestrak_line(line: &str) -> Result<Option<EopEntry>, ()>
+    {
+        let values: Vec<&str> = line.split_whitespace().collect();
+
+        // Headers, keywords and comments don't start with a year
+        let is_data = values.len() >= 7 && values[0].len() == 4 && values[0].chars().all(|c| c.is_ascii_digit());
+
+        if !is_data {
+            return Ok(None);
+        }
+
+        let number = |i: usize| values[i].parse::<f64>().map_err(|_| ());
+
+        Ok(Some(EopEntry {
+            mjd: number(3)?,
+            x_pole: number(4)? * ARCSECONDS_TO_RAD,
+            y_pole: number(5)? * ARCSECONDS_TO_RAD,
+            ut1_minus_utc: number(6)?
+        }))
+    }
+
+    // Orientation at the given UTC instant, linearly interpolated, none outside the table
+    pub fn get(&self, utc: &JulianDate) -> Option<EopEntry>
+    {
+        let mjd = utc.mjd();
+
+        if self.entries.last().is_some_and(|last| last.mjd == mjd) {
+            return self.entries.last().copied();
+        }
+
+        // First day after the instant
+        let next = self.entries.partition_point(|entry| entry.mjd <= mjd);
+
+        if next == 0 || next == self.entries.len() {
+            return None;
+        }
+
+        let (before, after) = (self.entries[next - 1], self.entries[next]);
+        let t = (mjd - before.mjd) / (after.mjd - before.mjd);
+
+        // UT1-UTC jumps by a second on leap seconds, that jump is not interpolated
+        let mut ut1_step = after.ut1_minus_utc - before.ut1_minus_utc;
+
+        if ut1_step.abs() > 0.5 {
+            ut1_step -= ut1_step.round();
+        }
+
+        Some(EopEntry {
+            mjd: mjd,
+            x_pole: before.x_pole + t * (after.x_pole - before.x_pole),
+            y_pole: before.y_pole + t * (after.y_pole - before.y_pole),
+            ut1_minus_utc: before.ut1_minus_utc + t * ut1_step
+        })
+    }
+
+    pub fn get_entries(&self) -> &Vec<EopEntry>
+    {
+        return &self.entries;
+    }
+}
+
+// Converts UTC instants to the other time scales, UT1 is taken as UTC (less than 0.9 s off) without EOP data
+#[derive(Clone, Debug, Default)]
+pub struct TimeScales {
+    leap_seconds: LeapSeconds,
+    eop: Option<EopTable>
+}
+
+impl TimeScales
+{
+    pub fn new(leap_seconds: LeapSeconds, eop: Option<EopTable>) -> Self
+    {
+        TimeScales {
+            leap_seconds: leap_seconds,
+            eop: eop
+        }
+    }
+
+    // Offset from UTC in s of the given scale at the given UTC instant
+    pub fn offset_from_utc(&self, scale: TimeScale, utc: &JulianDate) -> f64
+    {
+        let tai = self.leap_seconds.tai_minus_utc(utc);
+
+        match scale {
+            TimeScale::UTC => 0.0,
+            TimeScale::TAI => tai,
+            TimeScale::TT => tai + TT_MINUS_TAI,
+            TimeScale::GPS => tai - TAI_MINUS_GPS,
+            TimeScale::UT1 => self.ut1_minus_utc(utc),
+        }
+    }
+
+    // Julian date in the given scale of an UTC instant
+    pub fn convert(&self, scale: TimeScale, utc: DateTime<Utc>) -> JulianDate
+    {
+        let utc = JulianDate::from_datetime(utc);
+
+        utc.add_days(self.offset_from_utc(scale, &utc) / SECONDS_PER_DAY)
+    }
+
+    pub fn tai(&self, utc: DateTime<Utc>) -> JulianDate
+    {
+        self.convert(TimeScale::TAI, utc)
+    }
+
+    pub fn tt(&self, utc: DateTime<Utc>) -> JulianDate
+    {
+        self.convert(TimeScale::TT, utc)
+    }
+
+    pub fn gps(&self, utc: DateTime<Utc>) -> JulianDate
+    {
+        self.convert(TimeScale::GPS, utc)
+    }
+
+    pub fn ut1(&self, utc: DateTime<Utc>) -> JulianDate
+    {
+        self.convert(TimeScale::UT1, utc)
+    }
+
+    // UT1-UTC in s, zero when there is no EOP data for the instant
+    pub fn ut1_minus_utc(&self, utc: &JulianDate) -> f64
+    {
+        return self.get_eop(utc).map_or(0.0, |entry| entry.ut1_minus_utc);
+    }
+
+    // Earth orientation at the given UTC instant, if loaded and inside the table
+    pub fn get_eop(&self, utc: &JulianDate) -> Option<EopEntry>
+    {
+        return self.eop.as_ref().and_then(|eop| eop.get(utc));
+    }
+
+    pub fn get_leap_seconds(&self) -> &LeapSeconds
+    {
+        return &self.leap_seconds;
+    }
+
+    pub fn set_leap_seconds(&mut self, leap_seconds: LeapSeconds)
+    {
+        self.leap_seconds = leap_seconds;
+    }
+
+    pub fn set_eop(&mut self, eop: Option<EopTable>)
+    {
+        self.eop = eop;
+    }
+}
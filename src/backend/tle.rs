@@ -1,7 +1,152 @@
+use super::error::LoadError;
+use super::time::JulianDate;
+
+use chrono::{DateTime, NaiveDate, TimeDelta, Utc};
+use std::fmt;
 use std::fs;
+use std::io::BufRead;
+
+// How the fixed-column format of the element set is checked before parsing
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum ValidationMode {
+    // Any format error rejects the element set
+    #[default]
+    Strict,
+    // Format errors are kept as warnings on the TLE
+    Lenient,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ValidationError {
+    /// The line is not 69 characters long
+    LineLength { line: u8, length: usize },
+
+    /// The line doesn't start with its line number
+    LineNumber { line: u8 },
+
+    /// A column that must be blank holds something else
+    Separator { line: u8, column: usize },
+
+    /// The last column doesn't match the modulo 10 checksum of the line
+    Checksum { line: u8, expected: u32 },
+
+    /// Lines 1 and 2 belong to different satellites
+    CatalogNumberMismatch,
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ValidationError::LineLength { line, length } => write!(f, "line {} has {} characters instead of 69", line, length),
+            ValidationError::LineNumber { line } => write!(f, "line {} doesn't start with {}", line, line),
+            ValidationError::Separator { line, column } => write!(f, "line {} col {} should be blank", line, column),
+            ValidationError::Checksum { line, expected } => write!(f, "line {} checksum should be {}", line, expected),
+            ValidationError::CatalogNumberMismatch => write!(f, "catalog numbers of lines 1 and 2 don't match"),
+        }
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+// Position of a field in the element set, the columns start from 1 as in the format specification
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Field {
+    pub line: u8,
+    pub start: usize,
+    pub end: usize,
+    pub name: &'static str,
+}
+
+impl Field {
+    pub const CATALOG_NUMBER: Field = Field::new(1, 3, 7, "catalog number");
+    pub const CLASSIFICATION: Field = Field::new(1, 8, 8, "classification");
+    pub const DESIGNATOR: Field = Field::new(1, 10, 17, "international designator");
+    pub const LAUNCH_YEAR: Field = Field::new(1, 10, 11, "launch year");
+    pub const LAUNCH_NUMBER: Field = Field::new(1, 12, 14, "launch number");
+    pub const LAUNCH_PIECE: Field = Field::new(1, 15, 17, "launch piece");
+    pub const EPOCH_YEAR: Field = Field::new(1, 19, 20, "epoch year");
+    pub const EPOCH_DAY: Field = Field::new(1, 21, 32, "epoch day");
+    pub const MEAN_MOTION_DOT: Field = Field::new(1, 34, 43, "first derivative of mean motion");
+    pub const MEAN_MOTION_DDOT: Field = Field::new(1, 45, 52, "second derivative of mean motion");
+    pub const DRAG_TERM: Field = Field::new(1, 54, 61, "drag term");
+    pub const EPHEMERIS_TYPE: Field = Field::new(1, 63, 63, "ephemeris type");
+    pub const ELEMENT_SET_NUMBER: Field = Field::new(1, 65, 68, "element set number");
+    pub const INCLINATION: Field = Field::new(2, 9, 16, "inclination");
+    pub const RIGHT_ASCENSION: Field = Field::new(2, 18, 25, "right ascension");
+    pub const ECCENTRICITY: Field = Field::new(2, 27, 33, "eccentricity");
+    pub const ARGUMENT_OF_PERIGEE: Field = Field::new(2, 35, 42, "argument of perigee");
+    pub const MEAN_ANOMALY: Field = Field::new(2, 44, 51, "mean anomaly");
+    pub const MEAN_MOTION: Field = Field::new(2, 53, 63, "mean motion");
+    pub const REVOLUTION_NUMBER: Field = Field::new(2, 64, 68, "revolution number");
+
+    const fn new(line: u8, start: usize, end: usize, name: &'static str) -> Self
+    {
+        Field { line, start, end, name }
+    }
+}
+
+impl fmt::Display for Field {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.start == self.end {
+            write!(f, "line {} col {}: {}", self.line, self.start, self.name)
+        } else {
+            write!(f, "line {} col {}-{}: {}", self.line, self.start, self.end, self.name)
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum TleError {
+    /// The element set ends before the given line
+    MissingLine { line: u8 },
+
+    /// A field is missing or can't be parsed
+    InvalidField(Field),
+
+    /// Fixed-column format error, only returned in strict mode
+    Validation(ValidationError),
+
+    /// The catalog number can't be written, not even in Alpha-5
+    CatalogNumberRange(i32),
+
+    /// The value of a field doesn't fit in its columns when writing it
+    Overflow(Field),
+
+    /// A written line doesn't fit in the 69 columns
+    LineOverflow { line: u8 },
+}
 
+impl fmt::Display for TleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TleError::MissingLine { line } => write!(f, "line {} is missing", line),
+            TleError::InvalidField(field) => write!(f, "{}", field),
+            TleError::Validation(error) => write!(f, "{}", error),
+            TleError::CatalogNumberRange(number) => write!(f, "catalog number {} is out of the Alpha-5 range", number),
+            TleError::Overflow(field) => write!(f, "{} doesn't fit in its columns", field),
+            TleError::LineOverflow { line } => write!(f, "line {} doesn't fit in 69 columns", line),
+        }
+    }
+}
+
+impl std::error::Error for TleError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            TleError::Validation(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone)]
 pub struct TLE {
 
+    /// Name of the object, only present on three line element sets
+    pub name: Option<String>,
+
+    /// Format errors found when parsed in lenient mode
+    pub warnings: Vec<ValidationError>,
+
     /// Catalog Number
     pub catalog_number: i32,
 
@@ -11,8 +156,11 @@ pub struct TLE {
     /// Launch Year
     pub launch_year: i32,
 
-    /// Launch Piece
-    pub launch_piece: char,
+    /// Launch number of the year
+    pub launch_number: i32,
+
+    /// Launch Piece, up to three letters
+    pub launch_piece: String,
 
     /// Epoch Year
     pub epoch_year: i32,
@@ -20,12 +168,27 @@ pub struct TLE {
     /// Epoch Day Of Year
     pub epoch_day: f64,
 
-    /// Ballistic Coefficient
+    /// Epoch as an UTC instant, rounded to the microsecond
+    pub epoch: DateTime<Utc>,
+
+    /// Julian date of the epoch, split in the day at 0h and its fraction to not lose precision
+    pub epoch_jd: JulianDate,
+
+    /// Ballistic Coefficient, first derivative of the mean motion divided by two in rev.day⁻²
     pub ballistic_coefficient: f64,
 
+    /// Second derivative of the mean motion divided by six in rev.day⁻³
+    pub mean_motion_ddot: f64,
+
     /// B star
     pub  drag_term: f64,
 
+    /// Ephemeris type, always 0 for distributed element sets
+    pub ephemeris_type: u8,
+
+    /// Element set number, incremented when a new TLE is generated
+    pub element_set_number: i32,
+
     /// Angle between the equator and the orbit plane in rad
     pub inclination: f64,
 
@@ -42,159 +205,484 @@ pub struct TLE {
     pub mean_anomaly: f64,
 
     /// Mean number of orbits per day in rad.min⁻¹
-    pub mean_motion: f64
+    pub mean_motion: f64,
+
+    /// Number of revolutions at epoch
+    pub revolution_number: i32
 }
 
 impl TLE
 {
-    const TLE_ERROR: &'static str = "ERROR::TLE: Invalid TLE format";
-    const TLE_PARSING_ERROR: &'static str = "ERROR::TLE: Unable to parse provided data";
+    const LINE_LENGTH: usize = 69;
+
+    // Letters used by Alpha-5 catalog numbers, I and O are skipped to avoid confusing them with 1 and 0
+    const ALPHA_5_LETTERS: &'static str = "ABCDEFGHJKLMNPQRSTUVWXYZ";
+
+    // Columns, starting from 1, that separate the fields of each line
+    const LINE_1_SEPARATORS: [usize; 8] = [2, 9, 18, 33, 44, 53, 62, 64];
+    const LINE_2_SEPARATORS: [usize; 7] = [2, 8, 17, 26, 34, 43, 52];
+
+    pub fn new(file_path: &str) -> Result<Self, LoadError>
+    {
+        let contents = fs::read_to_string(file_path)?;
+
+        Ok(Self::parse(&contents)?)
+    }
 
-    pub fn new(file_path: &str) -> Result<Self, &str>
+    // Reads the next element set of the reader, the lines after it are left for the next call.
+    // None when only blank lines were left, an element set cut by the end of the reader is an error
+    pub fn from_reader<R: BufRead>(reader: &mut R, mode: ValidationMode) -> Result<Option<Self>, LoadError>
     {
-        let contents = fs::read_to_string(file_path).expect("ww");
+        let mut lines = Vec::new();
+        let mut buffer = String::new();
 
-        let mut lines = contents.lines();
+        // Name line (optional), line 1 and line 2
+        while lines.len() < 3 && lines.last().is_none_or(|line: &String| !line.starts_with("2 ")) {
+            buffer.clear();
+
+            if reader.read_line(&mut buffer)? == 0 {
+                break;
+            }
+
+            if !buffer.trim().is_empty() {
+                lines.push(buffer.trim_end().to_string());
+            }
+        }
+
+        if lines.is_empty() {
+            return Ok(None);
+        }
+
+        Ok(Some(Self::parse_with_mode(&lines.join("\n"), mode)?))
+    }
+
+    // Parses the element set from the contents of a TLE file, with or without the name line
+    pub fn parse(contents: &str) -> Result<Self, TleError>
+    {
+        Self::parse_with_mode(contents, ValidationMode::Strict)
+    }
+
+    pub fn parse_with_mode(contents: &str, mode: ValidationMode) -> Result<Self, TleError>
+    {
+        let mut lines = contents.lines().filter(|line| !line.trim().is_empty());
 
         // Go to next, if an errors occur return the error
-        let mut current_line = lines.next().ok_or(Self::TLE_ERROR)?;
+        let mut current_line = lines.next().ok_or(TleError::MissingLine { line: 1 })?;
+
+        // Three line element sets start with the name, Space-Track prefixes it with "0 "
+        let mut name = None;
+
+        if !current_line.starts_with("1 ") {
+            let name_line = current_line.strip_prefix("0 ").unwrap_or(current_line);
+
+            name = Some(name_line.trim().to_string());
+            current_line = lines.next().ok_or(TleError::MissingLine { line: 1 })?;
+        }
+
+        let second_line = lines.next().ok_or(TleError::MissingLine { line: 2 })?;
+
+        Self::from_lines(name.as_deref(), current_line, second_line, mode)
+    }
+
+    // Parses the element set from its lines, the name is the one of three line element sets
+    pub fn from_lines(name: Option<&str>, first_line: &str, second_line: &str, mode: ValidationMode) -> Result<Self, TleError>
+    {
+        let warnings = Self::validate(first_line, second_line);
+
+        if let (ValidationMode::Strict, Some(error)) = (mode, warnings.first()) {
+            return Err(TleError::Validation(*error));
+        }
 
-        let mut columns = current_line.split_whitespace();
-        columns.next();
+        let lines = [first_line, second_line];
 
         // ---------------- General Information ----------------
         // Catalog number
-        let mut current_column = columns.next().ok_or(Self::TLE_ERROR)?;
-        let catalog_number = (&current_column[..5]).parse::<i32>().or_else(|e| return Err(Self::TLE_PARSING_ERROR))?;
+        let catalog_number = Self::decode_catalog_number(Self::field(&lines, Field::CATALOG_NUMBER)?)?;
 
         // Classification 
-        let classification = match current_column.chars().nth(5).ok_or(Self::TLE_ERROR)?
-        {
-            'U' =>  "Unclassified",
-            'C' =>  "Classified",
-            'A' =>  "Secret",
-            _ =>    "Unknown",
-        };
+        let classification = Self::classification(Self::field(&lines, Field::CLASSIFICATION)?);
 
-        // Launch year
-        current_column = columns.next().ok_or(Self::TLE_ERROR)?;
-        let year_str = &current_column[..2];
+        // International designator, some objects don't have one
+        let mut launch_year = 0;
+        let mut launch_number = 0;
+        let mut launch_piece = String::new();
 
-        let mut launch_year = year_str.parse::<i32>().or_else(|e| return Err(Self::TLE_PARSING_ERROR))?;
-        if launch_year < 57
+        if !Self::field(&lines, Field::DESIGNATOR)?.trim().is_empty()
         {
-            launch_year += 2000; 
+            launch_year = Self::full_year(Self::parse_field::<i32>(&lines, Field::LAUNCH_YEAR)?);
+            launch_number = Self::parse_field::<i32>(&lines, Field::LAUNCH_NUMBER)?;
+            launch_piece = Self::field(&lines, Field::LAUNCH_PIECE)?.trim().to_string();
         }
-        else
-        {
-            launch_year += 1900;
-        }
-        
-        // Launch piece
-        let launch_piece = current_column.chars().nth(5).ok_or(Self::TLE_ERROR)?;
 
         // ---------------- Epoch Orbit Information ----------------
 
         // Epoch Year
-        current_column = columns.next().ok_or(Self::TLE_ERROR)?;
-        let year_str = &current_column[..2];
-
-        let mut epoch_year = year_str.parse::<i32>().or_else(|e| return Err(Self::TLE_PARSING_ERROR))?;
-        if epoch_year < 57
-        {
-            epoch_year += 2000; 
-        }
-        else
-        {
-            epoch_year += 1900;
-        }
+        let epoch_year = Self::full_year(Self::parse_field::<i32>(&lines, Field::EPOCH_YEAR)?);
 
         // Epoch Day of Year
-        let day_str = &current_column[2..];
-        let epoch_day = day_str.parse::<f64>().or_else(|e| return Err(Self::TLE_PARSING_ERROR))?; // TODO: Error handling
+        let epoch_day = Self::parse_field::<f64>(&lines, Field::EPOCH_DAY)?;
+
+        let epoch = Self::epoch_from_day(epoch_year, epoch_day).ok_or(TleError::InvalidField(Field::EPOCH_DAY))?;
+        let epoch_jd = JulianDate::from_day_of_year(epoch_year, epoch_day).ok_or(TleError::InvalidField(Field::EPOCH_DAY))?;
 
         // Ballistic Coefficient
-        current_column = columns.next().ok_or(Self::TLE_ERROR)?;
-        let ballistic_coefficient = current_column.parse::<f64>().or_else(|e| return Err(Self::TLE_PARSING_ERROR))?;
+        let ballistic_coefficient = Self::parse_field::<f64>(&lines, Field::MEAN_MOTION_DOT)?;
 
-        // Drag Term
-        columns.next(); // Second derivatice of mean motion
-        current_column = columns.next().ok_or(Self::TLE_ERROR)?;
+        // Second derivative of mean motion, decimal point assumed
+        let mean_motion_ddot = Self::parse_exponent(&lines, Field::MEAN_MOTION_DDOT)?;
 
-        let temp = {
-        if current_column.chars().nth(0).ok_or(Self::TLE_ERROR)? == '-'
-        {
-            format!("-0.{}e{}", &current_column[1..6], &current_column[6..]) // Decimal point assumed
-        }
-        else
-        {
-            format!("0.{}e{}", &current_column[0..5], &current_column[5..])
-        }};
-        let drag_term = temp.parse::<f64>().or_else(|e| return Err(Self::TLE_PARSING_ERROR))?;
-
-        // Inclination (radians)
-        current_line = lines.next().ok_or(Self::TLE_ERROR)?;
-        columns = current_line.split_whitespace();
+        // Drag Term, decimal point assumed
+        let drag_term = Self::parse_exponent(&lines, Field::DRAG_TERM)?;
 
-        columns.next();                         // Line number
-        columns.next();                         // Catalog number
+        // Ephemeris type and element set number, left blank by some sources
+        let ephemeris_type = Self::parse_optional_field::<u8>(&lines, Field::EPHEMERIS_TYPE)?;
+        let element_set_number = Self::parse_optional_field::<i32>(&lines, Field::ELEMENT_SET_NUMBER)?;
 
-        current_column = columns.next().ok_or(Self::TLE_ERROR)?;
-        let inclination = (current_column.parse::<f64>().or_else(|e| return Err(Self::TLE_PARSING_ERROR))? * core::f64::consts::PI) / 180.0;
+        // Inclination (radians)
+        let inclination = (Self::parse_field::<f64>(&lines, Field::INCLINATION)? * core::f64::consts::PI) / 180.0;
 
         // Right ascension (radians)
-        current_column = columns.next().ok_or(Self::TLE_ERROR)?;
-        let right_ascension = (current_column.parse::<f64>().or_else(|e| return Err(Self::TLE_PARSING_ERROR))? * core::f64::consts::PI) / 180.0;
+        let right_ascension = (Self::parse_field::<f64>(&lines, Field::RIGHT_ASCENSION)? * core::f64::consts::PI) / 180.0;
 
-        // Eccentricity
-        current_column = columns.next().ok_or(Self::TLE_ERROR)?;
-        let eccentricity_str = format!("0.{}", current_column);
-        let eccentricity = eccentricity_str.parse::<f64>().or_else(|e| return Err(Self::TLE_PARSING_ERROR))?;
+        // Eccentricity, decimal point assumed
+        let eccentricity_str = format!("0.{}", Self::field(&lines, Field::ECCENTRICITY)?.trim());
+        let eccentricity = eccentricity_str.parse::<f64>().map_err(|_| TleError::InvalidField(Field::ECCENTRICITY))?;
 
         // Argument of Perigee
-        current_column = columns.next().ok_or(Self::TLE_ERROR)?;
-        let argument_of_perigee = (current_column.parse::<f64>().or_else(|e| return Err(Self::TLE_PARSING_ERROR))? * core::f64::consts::PI) / 180.0;
+        let argument_of_perigee = (Self::parse_field::<f64>(&lines, Field::ARGUMENT_OF_PERIGEE)? * core::f64::consts::PI) / 180.0;
 
-        
         // Mean Anomaly (radians)
-        current_column = columns.next().ok_or(Self::TLE_ERROR)?;
-        let mean_anomaly = (current_column.parse::<f64>().or_else(|e| return Err(Self::TLE_PARSING_ERROR))? * core::f64::consts::PI) / 180.0;
+        let mean_anomaly = (Self::parse_field::<f64>(&lines, Field::MEAN_ANOMALY)? * core::f64::consts::PI) / 180.0;
 
         // Mean Motion (radians/min)
-        current_column = columns.next().ok_or(Self::TLE_ERROR)?;
-        let mean_motion = (current_column.parse::<f64>().or_else(|e| return Err(Self::TLE_PARSING_ERROR))? * 2.0*core::f64::consts::PI) / 1440.0;
+        let mean_motion = (Self::parse_field::<f64>(&lines, Field::MEAN_MOTION)? * 2.0*core::f64::consts::PI) / 1440.0;
+
+        // Revolution number at epoch
+        let revolution_number = Self::parse_optional_field::<i32>(&lines, Field::REVOLUTION_NUMBER)?;
         
         Ok(TLE{
+            name: name.map(|name| name.to_string()),
+            warnings: warnings,
             catalog_number: catalog_number,
             classification: classification.to_string(),
             launch_year: launch_year,
+            launch_number: launch_number,
             launch_piece: launch_piece,
             epoch_year: epoch_year,
             epoch_day: epoch_day,
+            epoch: epoch,
+            epoch_jd: epoch_jd,
             ballistic_coefficient: ballistic_coefficient,
+            mean_motion_ddot: mean_motion_ddot,
             drag_term:  drag_term,
+            ephemeris_type: ephemeris_type,
+            element_set_number: element_set_number,
             inclination: inclination,
             right_ascension: right_ascension,
             eccentricity: eccentricity,
             argument_of_perigee: argument_of_perigee,
             mean_anomaly: mean_anomaly,
-            mean_motion: mean_motion
+            mean_motion: mean_motion,
+            revolution_number: revolution_number
         })
     }
 
+    // UTC instant of the given day of the year, being 1.0 the start of January 1st, rounded to the microsecond
+    pub fn epoch_from_day(year: i32, day_of_year: f64) -> Option<DateTime<Utc>>
+    {
+        let day = day_of_year.floor();
+        let microseconds = ((day_of_year - day) * 86400.0e6).round() as i64;
+
+        let midnight = NaiveDate::from_yo_opt(year, day as u32)?.and_hms_opt(0, 0, 0)?;
+
+        Some((midnight + TimeDelta::microseconds(microseconds)).and_utc())
+    }
+
+    // Inverse of classification, anything unknown is written as unclassified
+    pub fn classification_code(classification: &str) -> char
+    {
+        match classification
+        {
+            "Classified" => 'C',
+            "Secret" =>     'S',
+            _ =>            'U',
+        }
+    }
+
+    pub fn classification(code: &str) -> &'static str
+    {
+        match code
+        {
+            "U" =>  "Unclassified",
+            "C" =>  "Classified",
+            "S" =>  "Secret",
+            _ =>    "Unknown",
+        }
+    }
+
+    // Text between the columns of the field, both included
+    fn field<'a>(lines: &[&'a str; 2], field: Field) -> Result<&'a str, TleError>
+    {
+        let line = lines[field.line as usize - 1];
+
+        return line.get(field.start - 1..field.end).ok_or(TleError::InvalidField(field));
+    }
+
+    fn parse_field<T: std::str::FromStr>(lines: &[&str; 2], field: Field) -> Result<T, TleError>
+    {
+        return Self::field(lines, field)?.trim().parse::<T>().map_err(|_| TleError::InvalidField(field));
+    }
+
+    // Same as parse_field but a blank or missing field is zero
+    fn parse_optional_field<T: std::str::FromStr + Default>(lines: &[&str; 2], field: Field) -> Result<T, TleError>
+    {
+        let line = lines[field.line as usize - 1];
+        let text = line.get(field.start - 1..field.end.min(line.len())).unwrap_or("").trim();
+
+        if text.is_empty() {
+            return Ok(T::default());
+        }
+
+        return text.parse::<T>().map_err(|_| TleError::InvalidField(field));
+    }
+
+    // Fields like " 24028-3" have a sign, five digits with the decimal point assumed before them and an exponent
+    fn parse_exponent(lines: &[&str; 2], field: Field) -> Result<f64, TleError>
+    {
+        let text = Self::field(lines, field)?;
+
+        let (mantissa, exponent) = text.split_at_checked(6).ok_or(TleError::InvalidField(field))?;
+        let (sign, digits) = mantissa.split_at_checked(1).ok_or(TleError::InvalidField(field))?;
+
+        let value = format!("{}.{}e{}", sign.trim(), digits, exponent.trim());
+
+        return value.parse::<f64>().map_err(|_| TleError::InvalidField(field));
+    }
+
+    // Catalog numbers above 99999 use Alpha-5, the first digit is replaced by a letter starting at A = 10.
+    // The field is either five digits or a letter followed by four digits
+    pub fn decode_catalog_number(field: &str) -> Result<i32, TleError>
+    {
+        let digits = |text: &[u8]| text.iter().all(|b| b.is_ascii_digit());
+
+        let bytes = field.as_bytes();
+
+        if bytes.len() != 5 || !digits(&bytes[1..]) {
+            return Err(TleError::InvalidField(Field::CATALOG_NUMBER));
+        }
+
+        // Only ASCII digits are left after the first byte, so the field can be split there
+        let rest = field[1..].parse::<i32>().map_err(|_| TleError::InvalidField(Field::CATALOG_NUMBER))?;
+
+        if bytes[0].is_ascii_digit() {
+            return Ok((bytes[0] - b'0') as i32 * 10000 + rest);
+        }
+
+        let letter = Self::ALPHA_5_LETTERS.find(bytes[0] as char).ok_or(TleError::InvalidField(Field::CATALOG_NUMBER))? as i32;
+
+        return Ok((letter + 10) * 10000 + rest);
+    }
+
+    // Five character catalog number field, Alpha-5 is only used above 99999
+    pub fn encode_catalog_number(number: i32) -> Result<String, TleError>
+    {
+        if !(0..340000).contains(&number) {
+            return Err(TleError::CatalogNumberRange(number));
+        }
+
+        if number < 100000 {
+            return Ok(format!("{:05}", number));
+        }
+
+        let letter = Self::ALPHA_5_LETTERS.as_bytes()[(number / 10000 - 10) as usize] as char;
+
+        return Ok(format!("{}{:04}", letter, number % 10000));
+    }
+
+    // Two digit years from 57 belong to the 20th century
+    fn full_year(year: i32) -> i32
+    {
+        if year < 57
+        {
+            return year + 2000;
+        }
+
+        return year + 1900;
+    }
+
+    // Checks the fixed-column format of both lines, returns every error found
+    pub fn validate(first_line: &str, second_line: &str) -> Vec<ValidationError>
+    {
+        let mut errors = Vec::new();
+
+        let lines = [
+            (1, first_line.trim_end(), &Self::LINE_1_SEPARATORS[..]),
+            (2, second_line.trim_end(), &Self::LINE_2_SEPARATORS[..]),
+        ];
+
+        for (number, line, separators) in lines {
+            let chars: Vec<char> = line.chars().collect();
+
+            if chars.first() != char::from_digit(number as u32, 10).as_ref() {
+                errors.push(ValidationError::LineNumber { line: number });
+            }
+
+            for &column in separators {
+                if chars.get(column - 1).is_some_and(|c| *c != ' ') {
+                    errors.push(ValidationError::Separator { line: number, column: column });
+                }
+            }
+
+            // The checksum is only meaningful when it is on its column
+            if chars.len() != Self::LINE_LENGTH {
+                errors.push(ValidationError::LineLength { line: number, length: chars.len() });
+                continue;
+            }
+
+            let expected = Self::checksum(&chars[..Self::LINE_LENGTH - 1].iter().collect::<String>());
+
+            if chars[Self::LINE_LENGTH - 1].to_digit(10) != Some(expected) {
+                errors.push(ValidationError::Checksum { line: number, expected: expected });
+            }
+        }
+
+        if first_line.get(2..7) != second_line.get(2..7) {
+            errors.push(ValidationError::CatalogNumberMismatch);
+        }
+
+        errors
+    }
+
+    // Modulo 10 sum of the digits of the line, minus signs count as 1
+    pub fn checksum(line: &str) -> u32
+    {
+        let sum: u32 = line
+            .chars()
+            .map(|c| match c {
+                '-' => 1,
+                _ => c.to_digit(10).unwrap_or(0),
+            })
+            .sum();
+
+        return sum % 10;
+    }
+
+    // Element set as the two fixed-column lines with their checksums
+    pub fn to_lines(&self) -> Result<[String; 2], TleError>
+    {
+        let catalog_number = Self::encode_catalog_number(self.catalog_number)?;
+
+        // International designator, blank if the object doesn't have one
+        let designator = if self.launch_year == 0 && self.launch_piece.is_empty() {
+            " ".repeat(8)
+        } else {
+            format!("{:02}{:03}{:<3}", self.launch_year % 100, self.launch_number, self.launch_piece)
+        };
+
+        let mut first_line = format!("1 {}{} {} {:02}{:012.8} {} {} {} {} {:>4}",
+            catalog_number,
+            Self::classification_code(&self.classification),
+            designator,
+            self.epoch_year % 100,
+            self.epoch_day,
+            Self::format_derivative(self.ballistic_coefficient)?,
+            Self::format_exponent(self.mean_motion_ddot, Field::MEAN_MOTION_DDOT)?,
+            Self::format_exponent(self.drag_term, Field::DRAG_TERM)?,
+            self.ephemeris_type,
+            self.element_set_number % 10000);
+
+        let mut second_line = format!("2 {} {:8.4} {:8.4} {:07} {:8.4} {:8.4} {:11.8}{:>5}",
+            catalog_number,
+            self.inclination.to_degrees(),
+            self.right_ascension.to_degrees(),
+            (self.eccentricity * 1.0e7).round() as u32,
+            self.argument_of_perigee.to_degrees(),
+            self.mean_anomaly.to_degrees(),
+            (self.mean_motion * 1440.0) / (2.0*core::f64::consts::PI),
+            self.revolution_number % 100000);
+
+        if first_line.len() != Self::LINE_LENGTH - 1 {
+            return Err(TleError::LineOverflow { line: 1 });
+        }
+
+        if second_line.len() != Self::LINE_LENGTH - 1 {
+            return Err(TleError::LineOverflow { line: 2 });
+        }
+
+        first_line.push_str(&Self::checksum(&first_line).to_string());
+        second_line.push_str(&Self::checksum(&second_line).to_string());
+
+        Ok([first_line, second_line])
+    }
+
+    // Element set as it would be in a file, with the name line if the object has one
+    pub fn to_text(&self) -> Result<String, TleError>
+    {
+        let [first_line, second_line] = self.to_lines()?;
+
+        match &self.name {
+            Some(name) => Ok(format!("{}\n{}\n{}\n", name, first_line, second_line)),
+            None => Ok(format!("{}\n{}\n", first_line, second_line)),
+        }
+    }
+
+    // First derivative of the mean motion as " .00013667", the leading zero is dropped
+    fn format_derivative(value: f64) -> Result<String, TleError>
+    {
+        let digits = format!("{:.8}", value.abs());
+        let digits = digits.strip_prefix('0').ok_or(TleError::Overflow(Field::MEAN_MOTION_DOT))?;
+
+        let sign = if value < 0.0 { '-' } else { ' ' };
+
+        Ok(format!("{}{}", sign, digits))
+    }
+
+    // Inverse of parse_exponent, 0.00024028 is written as " 24028-3"
+    fn format_exponent(value: f64, field: Field) -> Result<String, TleError>
+    {
+        // Values too small for a one digit exponent are written as zero
+        if value.abs() < 1.0e-10 {
+            return Ok(String::from(" 00000-0"));
+        }
+
+        let sign = if value < 0.0 { '-' } else { ' ' };
+
+        // Exponent that leaves the mantissa between 0.1 and 1
+        let mut exponent = value.abs().log10().floor() as i32 + 1;
+        let mut mantissa = (value.abs() / 10f64.powi(exponent) * 1.0e5).round() as u32;
+
+        if mantissa >= 100000 {
+            mantissa /= 10;
+            exponent += 1;
+        }
+
+        if exponent > 9 {
+            return Err(TleError::Overflow(field));
+        }
+
+        let exponent_sign = if exponent < 0 { '-' } else { '+' };
+
+        Ok(format!("{}{:05}{}{}", sign, mantissa, exponent_sign, exponent.abs()))
+    }
+
     pub fn print_data(&self)
     {
         println!("TLE Data: ");
         println!("  ----- General Information ----- ");
+        println!("Name:                       {}", self.name.as_deref().unwrap_or("-"));
         println!("Satellite Catalog Number:   {}", self.catalog_number);
         println!("Classification:             {}", self.classification);
         println!("Launch Year:                {}", self.launch_year);
+        println!("Launch Number:              {}", self.launch_number);
         println!("Launch Piece:               {}", self.launch_piece);
+        println!("Element Set Number:         {}", self.element_set_number);
 
         println!("\n  ----- Epoch Orbit Information ----- ");
         println!("Epoch Year:                 {}", self.epoch_year);
         println!("Epoch Day Of Year:          {}", self.epoch_day);
+        println!("Epoch:                      {}", self.epoch.format("%Y-%m-%d %H:%M:%S%.6f UTC"));
         println!("Ballistic Coefficient:      {}", self.ballistic_coefficient);
+        println!("Mean Motion 2nd Derivative: {}", self.mean_motion_ddot);
         println!("Drag Term:                  {}", self.drag_term);
         println!("Inclination(rads):          {}", self.inclination);
         println!("Right Ascension(rads):      {}", self.right_ascension);
@@ -202,10 +690,30 @@ impl TLE
         println!("Argument of Perigee(rads):  {}", self.argument_of_perigee);
         println!("Mean Anomaly(rads):         {}", self.mean_anomaly);
         println!("Mean Motion(radians/min):   {}", self.mean_motion);
+        println!("Revolution Number:          {}", self.revolution_number);
     }
 
     // Getters
 
+    pub fn get_name(&self) -> Option<&str>
+    {
+        return self.name.as_deref();
+    }
+
+    pub fn get_warnings(&self) -> &Vec<ValidationError>
+    {
+        return &self.warnings;
+    }
+
+    // Name to show for the object, two line element sets only have the catalog number
+    pub fn get_display_name(&self) -> String
+    {
+        match &self.name {
+            Some(name) => name.clone(),
+            None => format!("NORAD {}", self.catalog_number),
+        }
+    }
+
     pub fn get_catalog_number(&self) -> i32
     {
         return self.catalog_number;
@@ -221,9 +729,14 @@ impl TLE
         return self.launch_year;
     }
     
-    pub fn get_launch_piece(&self) -> char
+    pub fn get_launch_number(&self) -> i32
     {
-        return self.launch_piece;
+        return self.launch_number;
+    }
+
+    pub fn get_launch_piece(&self) -> &str
+    {
+        return &self.launch_piece;
     }
     
     pub fn get_epoch_year(&self) -> i32
@@ -235,16 +748,41 @@ impl TLE
     {
         return self.epoch_day;
     }
+
+    pub fn get_epoch(&self) -> DateTime<Utc>
+    {
+        return self.epoch;
+    }
+
+    pub fn get_epoch_julian_date(&self) -> JulianDate
+    {
+        return self.epoch_jd;
+    }
     
     pub fn get_ballistic_coefficient(&self) -> f64
     {
         return self.ballistic_coefficient;
     }
     
+    pub fn get_mean_motion_ddot(&self) -> f64
+    {
+        return self.mean_motion_ddot;
+    }
+
     pub fn get_drag_term(&self) -> f64
     {
         return self.drag_term;
     }
+
+    pub fn get_ephemeris_type(&self) -> u8
+    {
+        return self.ephemeris_type;
+    }
+
+    pub fn get_element_set_number(&self) -> i32
+    {
+        return self.element_set_number;
+    }
     
     pub fn get_inclination(&self) -> f64
     {
@@ -275,6 +813,11 @@ impl TLE
     {
         return self.mean_motion;
     }
+
+    pub fn get_revolution_number(&self) -> i32
+    {
+        return self.revolution_number;
+    }
 }
 
 
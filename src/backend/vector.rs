@@ -1,4 +1,6 @@
-#[derive(Clone)]
+use std::ops::{Add, Mul, Neg, Sub};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub struct PositionVector {
     pub x: f64,
     pub y: f64,
@@ -7,9 +9,6 @@ pub struct PositionVector {
 
 impl PositionVector {
 
-    const SEMI_MAYOR_AXIS: f64 = 6378.137; // Equatorial radius
-    const SEMI_MINOR_AXIS: f64 = 6356.752; // Polar radius
-
     pub fn new(x:f64, y: f64, z: f64) -> Self
     {
         Self {
@@ -22,6 +21,23 @@ impl PositionVector {
     pub fn dot(&self, other: &Self) -> f64 {
         self.x * other.x + self.y * other.y + self.z * other.z
     }
+
+    pub fn cross(&self, other: &Self) -> Self {
+        Self::new(
+            self.y * other.z - self.z * other.y,
+            self.z * other.x - self.x * other.z,
+            self.x * other.y - self.y * other.x
+        )
+    }
+
+    pub fn norm(&self) -> f64 {
+        self.dot(self).sqrt()
+    }
+
+    // Unit vector of the same direction, the zero vector has none and gives NaN
+    pub fn normalize(&self) -> Self {
+        *self * (1.0 / self.norm())
+    }
     
     pub fn sum(&mut self, other: &Self) {
         self.x += other.x;
@@ -66,83 +82,40 @@ impl PositionVector {
     {
         self.z
     }
+}
 
-    /* 
-     * The geodetic vetor should have the followiung format: (latitude, longitude, height)
-     * while the ecef will be: (x, y, z)
-    */
-    pub fn geodetic_to_ecef(&self) -> PositionVector
-    {
-        // https://en.wikipedia.org/wiki/Geographic_coordinate_conversion
-        // https://nssdc.gsfc.nasa.gov/planetary/factsheet/earthfact.html
-
-        // Compute ECEF coordinates
-
-        let n = self.prime_vertical_radius(self.x);
-
-        PositionVector::new(
-            (self.get_z() + n)*self.get_y().cos()*self.get_x().cos(), 
-            (self.get_z() + n)*self.get_y().sin()*self.get_x().cos(), 
-            (self.get_z() + n)*self.get_x().sin()
-        )
-    }
+impl Add for PositionVector {
+    type Output = Self;
 
-    pub fn ecef_to_geodetic(&self) -> PositionVector
+    fn add(self, other: Self) -> Self
     {
-        // https://en.wikipedia.org/wiki/Geodetic_coordinates
-
-        let p = (self.get_x().powi(2) + self.get_y().powi(2)).sqrt();
-
-        let lon = self.get_y().atan2(self.get_x());
-        let lat = (self.get_z() / p).atan();
-
-        let h = p / lat.cos();
-        
-        PositionVector::new(lat, lon, h - self.prime_vertical_radius(lat))
+        Self::new(self.x + other.x, self.y + other.y, self.z + other.z)
     }
+}
 
-    // Takes the sat vector as geodetic and using the client vector its computes the ENU coordinates
-    pub fn ecef_to_enu(client_ecef: &PositionVector, satellite_ecef: &PositionVector) -> PositionVector
-    {
-        // https://gssc.esa.int/navipedia/index.php/Transformations_between_ECEF_and_ENU_coordinates
-        
-        let client_geodetic = client_ecef.ecef_to_geodetic();
+impl Sub for PositionVector {
+    type Output = Self;
 
-        let mut p = satellite_ecef.clone();   // TODO: Better naming 
-    
-        p.sub(&client_ecef);
-    
-        let p_module = (p.get_x().powi(2) + p.get_y().powi(2) + p.get_z().powi(2)).sqrt();
-    
-        let p_normalized = PositionVector::new(p.get_x() / p_module, p.get_y() / p_module, p.get_z() / p_module); // TODO: implement as part of PositionVector
-    
-        // Apply rotation matrix
-        let lat = client_geodetic.get_x();
-        let lon = client_geodetic.get_y();
-    
-        let p_enu = PositionVector::new(-p_normalized.get_x()*lon.sin() + p_normalized.get_y()*lon.cos(),
-                                -p_normalized.get_x()*lon.cos()*lat.sin() - p_normalized.get_y()*lon.sin()*lat.sin() + p_normalized.get_z()*lat.cos(),
-                                p_normalized.get_x()*lon.cos()*lat.cos() + p_normalized.get_y()*lon.sin()*lat.cos() + p_normalized.get_z()*lat.sin());
-    
-        return p_enu.clone(); // (e, n, u)
+    fn sub(self, other: Self) -> Self
+    {
+        Self::new(self.x - other.x, self.y - other.y, self.z - other.z)
     }
+}
 
-    pub fn enu_to_azimuth_and_elevation(&self) -> PositionVector {
-        // https://gssc.esa.int/navipedia/index.php/Transformations_between_ECEF_and_ENU_coordinates
-        PositionVector::new(self.get_x().atan2(self.get_y()), self.get_z().asin(), 0.0) // (a, e, -)
-    }
+impl Mul<f64> for PositionVector {
+    type Output = Self;
 
-    // Computes the prime vertical radius of curvature
-    fn prime_vertical_radius(&self, latitude: f64) -> f64
+    fn mul(self, scalar: f64) -> Self
     {
-        let a = Self::SEMI_MAYOR_AXIS;
-        let b = Self::SEMI_MINOR_AXIS;
-
-        let e_squared = 1.0 - (b*b)/(a*a);
+        Self::new(self.x * scalar, self.y * scalar, self.z * scalar)
+    }
+}
 
-        let temp_1 = (latitude.sin()).powi(2);
-        let temp_2 = (1.0 - e_squared*temp_1).sqrt();
+impl Neg for PositionVector {
+    type Output = Self;
 
-        a / temp_2
+    fn neg(self) -> Self
+    {
+        Self::new(-self.x, -self.y, -self.z)
     }
 }
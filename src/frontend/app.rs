@@ -1,6 +1,11 @@
-use crate::Satellite;
-
-use crate::PositionVector;
+use crate::backend::catalog::Catalog;
+use crate::backend::coordinates::Geodetic;
+use crate::backend::ellipsoid::Ellipsoid;
+use crate::backend::observer::Observer;
+use crate::backend::satellite::Satellite;
+use crate::backend::timescale::{EopTable, LeapSeconds, TimeScales};
+use crate::backend::tle::{TLE, ValidationMode};
+use crate::frontend::clock::SimulationClock;
 
 use ratatui::{
     crossterm::event::KeyCode,
@@ -9,7 +14,9 @@ use ratatui::{
 
 use std::{
     fs,
-    cell::RefCell
+    cell::RefCell,
+    path::Path,
+    rc::Rc
 };
 
 pub enum MessageType {
@@ -52,6 +59,14 @@ impl Message {
     }
 }
 
+// What the typed text is for, if anything
+#[derive(Clone, Copy, PartialEq)]
+pub enum InputMode {
+    Normal,
+    Coordinates,
+    Date
+}
+
 pub struct TabsState<'a> {
     pub titles: Vec<&'a str>,
     pub index: usize,
@@ -123,12 +138,15 @@ impl<T> StatefulList<T> where T : Clone {
 pub struct App<'a> {  // TODO: Make em private
     pub title: &'a str,
     pub sat: Option<Satellite>,
-    pub tle_list: StatefulList<String>,
+    pub tle_list: StatefulList<TLE>,
+    pub validation: ValidationMode,
     pub tabs: TabsState<'a>,
     pub should_quit: bool,
-    pub usr_geodetic: PositionVector,
-    pub input_mode: bool,
+    pub observer: Observer,
+    pub input_mode: InputMode,
     pub buffer: String,
+    pub clock: SimulationClock,
+    pub time_scales: Rc<TimeScales>,
     messages: Vec<Message>
 }   
 
@@ -139,19 +157,28 @@ impl<'a> App<'a> {
 
     const INPUT_ARG_ERROR: &'static str = "Invalid number of arguments";
     const INPUT_TYPE_ERROR: &'static str = "Invalid type";
+    const INPUT_DATUM_ERROR: &'static str = "Unknown datum, expected WGS84, WGS72, GRS80 or Spherical";
     const TLE_ERROR: &'static str = "No TLE files found";
+    const DATE_ERROR: &'static str = "Invalid date, expected YYYY-MM-DD [HH:MM[:SS]]";
+
+    // Files of the IERS (and CelesTrak) read from the current directory, the first one found is used
+    const LEAP_SECOND_FILES: [&'static str; 2] = ["Leap_Second.dat", "leap-seconds.list"];
+    const EOP_FILES: [&'static str; 4] = ["finals2000A.all", "finals2000A.data", "finals2000A.daily", "EOP-All.txt"];
 
 
     pub fn new(title: &'a str) -> Self {
         Self {
             title,
             sat: None,
-            tle_list: StatefulList::new(Self::get_tle_files()),
+            tle_list: StatefulList::new(Vec::new()),
+            validation: ValidationMode::default(),
             tabs: TabsState::new(vec!["Map Projection", "Azimuthal Projection", "About"]),
             should_quit: false,
-            usr_geodetic: PositionVector::new(Self::DEF_LAT, Self::DEF_LON, 0.0),
-            input_mode: false,
+            observer: Observer::new(Geodetic::new(Self::DEF_LAT, Self::DEF_LON, 0.0), Ellipsoid::default()),
+            input_mode: InputMode::Normal,
             buffer: String::new(),
+            clock: SimulationClock::new(),
+            time_scales: Rc::new(TimeScales::default()),
             messages: Vec::new(),
         }
     }
@@ -166,10 +193,13 @@ impl<'a> App<'a> {
         self.messages.push(msg);
     }
 
-    // Set initial state by selecting the first TLE, if no TLE is found sends error message
+    // Set initial state by loading the catalogs and selecting the first TLE, if no TLE is found sends error message
     pub fn initialize(&mut self) {
+        self.time_scales = Rc::new(self.load_time_scales());
+        self.tle_list.items = self.load_catalogs();
+
         if let Some(tle) = self.tle_list.items.first() {
-            self.set_sat(tle.to_string());            
+            self.set_sat(tle.clone());            
         } else {
             self.push_message(Message::new(MessageType::Error, Self::TLE_ERROR.to_string()));
         }
@@ -179,8 +209,16 @@ impl<'a> App<'a> {
         self.sat.as_ref()
     }
 
-    pub fn get_usr_geodetic(&self) -> &PositionVector {
-        &self.usr_geodetic
+    pub fn get_observer(&self) -> &Observer {
+        &self.observer
+    }
+
+    pub fn get_usr_geodetic(&self) -> &Geodetic {
+        self.observer.get_geodetic()
+    }
+
+    pub fn get_usr_datum(&self) -> &Ellipsoid {
+        self.observer.get_ellipsoid()
     }
 
     pub fn get_messages(&self) -> &Vec<Message> {
@@ -188,11 +226,19 @@ impl<'a> App<'a> {
     }
 
     pub fn on_up(&mut self) {
+        if self.tle_list.items.is_empty() {
+            return;
+        }
+
         let tle = self.tle_list.previous().clone();
         self.set_sat(tle);
     }
 
     pub fn on_down(&mut self) {
+        if self.tle_list.items.is_empty() {
+            return;
+        }
+
         let tle = self.tle_list.next().clone();
         self.set_sat(tle);
     }
@@ -211,8 +257,18 @@ impl<'a> App<'a> {
                 self.should_quit = true;
             },
             KeyCode::Char('c') => {
-                self.input_mode = true;
+                self.input_mode = InputMode::Coordinates;
+            },
+            KeyCode::Char('t') => {
+                self.input_mode = InputMode::Date;
             },
+            // Simulation clock
+            KeyCode::Char(' ') => self.clock.toggle_pause(),
+            KeyCode::Char('f') => self.clock.speed_up(),
+            KeyCode::Char('.') => self.clock.step_forward(),
+            KeyCode::Char(',') => self.clock.step_backward(),
+            KeyCode::Char('s') => self.clock.next_step(),
+            KeyCode::Char('r') => self.clock.reset(),
             KeyCode::Enter => self.pop_message(),
             _ => {}
         }
@@ -220,6 +276,16 @@ impl<'a> App<'a> {
 
     pub fn on_key_input(&mut self, c: KeyCode) {
         match c {
+            KeyCode::Enter if self.input_mode == InputMode::Date => {
+                let result = SimulationClock::parse_date(&self.buffer);
+
+                self.visual_mode();
+
+                match result {
+                    Some(time) => self.clock.jump_to(time),
+                    None => self.push_message(Message::new(MessageType::Error, Self::get_error_msg(Self::DATE_ERROR))),
+                }
+            },
             KeyCode::Enter => {
                 // Process the current buffer
                 let result = Self::text_to_coordinates(self.buffer.clone());
@@ -231,9 +297,21 @@ impl<'a> App<'a> {
                 if  let Err(e) = result {
                     self.push_message(Message::new(MessageType::Error, e));
                 } else {
-                    self.usr_geodetic = result.unwrap();
+                    let (geodetic, datum) = result.unwrap();
+
+                    self.observer.set_geodetic(geodetic);
+
+                    // The ground track is drawn over the same datum as the user
+                    if let Some(datum) = datum {
+                        self.observer.set_ellipsoid(datum);
+
+                        if let Some(sat) = &mut self.sat {
+                            sat.set_ellipsoid(datum);
+                        }
+                    }
                 }
             },
+            KeyCode::Esc => self.visual_mode(),
             KeyCode::Backspace => {
                 self.buffer.pop();
             },
@@ -242,22 +320,20 @@ impl<'a> App<'a> {
         }
     }
 
-    fn set_sat(&mut self, tle: String) {
-        // Create new sat, if its an error, push it into the error Vec
-        let sat = Satellite::new(&tle);
-                    
-        if let Err(e) = sat {
-            self.push_message(Message::new(MessageType::Error, e.to_string()));
-        } else {
-            self.sat = sat.ok();
-        }
+    fn set_sat(&mut self, tle: TLE) {
+        let mut sat = Satellite::new(tle);
+
+        sat.set_time_scales(Rc::clone(&self.time_scales));
+        sat.set_ellipsoid(*self.get_usr_datum());
+        self.sat = Some(sat);
     }
 
     fn get_error_msg(msg: &str) -> String {
         format!("ERROR::APP: {}", msg)
     }
 
-    fn text_to_coordinates(text: String) -> Result<PositionVector, String> {
+    // Coordinates as "lat lon alt", optionally followed by the datum they are measured on
+    fn text_to_coordinates(text: String) -> Result<(Geodetic, Option<Ellipsoid>), String> {
         let mut columns = text.split_whitespace();
 
         let mut input = columns.next();
@@ -293,10 +369,17 @@ impl<'a> App<'a> {
         {    return Err(Self::get_error_msg(Self::INPUT_TYPE_ERROR));   } 
         let alt = value.unwrap();
 
-        Ok(PositionVector::new(lat, lon, alt))
+        // Get datum, the current one is kept if not given
+        let datum = match columns.next() {
+            Some(name) => Some(Ellipsoid::from_name(name).ok_or(Self::get_error_msg(Self::INPUT_DATUM_ERROR))?),
+            None => None,
+        };
+
+        Ok((Geodetic::new(lat, lon, alt), datum))
     }
 
-    fn get_tle_files() -> Vec<String> {
+    // Element sets of every TLE (.tle, .txt with element sets) and OMM file in the current directory, the ones that fail to parse are reported
+    fn load_catalogs(&mut self) -> Vec<TLE> {
         let mut tles = Vec::new();
     
         // Get the current directory
@@ -306,24 +389,98 @@ impl<'a> App<'a> {
         for entry in fs::read_dir(current_dir).expect("Failed to read directory") {
             let entry = entry.expect("Failed to read entry"); // TODO: Error handling
             
-            // Check if it's a file and if it ends with .tle
-            if entry.path().is_file() && entry.path().extension().map(|ext| ext == "tle").unwrap_or(false) {
-                tles.push(entry.file_name().to_string_lossy().to_string());
+            // Check if it's a file and if it ends with .tle, .txt (the extension used by Celestrak) or an OMM one
+            let is_catalog = entry.path().extension().map(|ext| {
+                ext == "tle" || ext == "txt" || Catalog::OMM_EXTENSIONS.iter().any(|omm| ext == *omm)
+            }).unwrap_or(false);
+
+            let file_name = entry.file_name().to_string_lossy().to_string();
+
+            if !entry.path().is_file() || !is_catalog || Self::EOP_FILES.contains(&file_name.as_str()) {
+                continue;
+            }
+
+            // Any text file can be in the directory, only the ones with element sets are catalogs
+            let is_text = entry.path().extension().is_some_and(|ext| ext == "txt");
+
+            if is_text && !fs::read_to_string(entry.path()).is_ok_and(|contents| Catalog::has_element_sets(&contents)) {
+                continue;
+            }
+
+            match Catalog::new(&file_name, self.validation) {
+                Ok(catalog) => {
+                    for error in catalog.get_errors() {
+                        let msg = format!("WARNING::CATALOG: {} {}", file_name, error);
+                        self.push_message(Message::new(MessageType::Warning, msg));
+                    }
+
+                    // Only element sets parsed in lenient mode can have format errors
+                    for tle in catalog.get_entries() {
+                        for warning in tle.get_warnings() {
+                            let msg = format!("WARNING::TLE: {} {}", tle.get_display_name(), warning);
+                            self.push_message(Message::new(MessageType::Warning, msg));
+                        }
+                    }
+
+                    tles.extend(catalog.get_entries().iter().cloned());
+                },
+                Err(e) => {
+                    let msg = format!("ERROR::CATALOG: {} {}", file_name, e);
+                    self.push_message(Message::new(MessageType::Error, msg));
+                },
             }
         }
 
         tles
     }
 
+    // Bundled leap seconds and no Earth orientation data, unless their files are in the current directory
+    fn load_time_scales(&mut self) -> TimeScales {
+        let mut time_scales = TimeScales::default();
+
+        if let Some(file_name) = Self::LEAP_SECOND_FILES.iter().find(|file| Path::new(file).is_file()) {
+            match LeapSeconds::new(file_name) {
+                Ok(leap_seconds) => time_scales.set_leap_seconds(leap_seconds),
+                Err(e) => {
+                    let msg = format!("ERROR::TIME: {} {}", file_name, e);
+                    self.push_message(Message::new(MessageType::Error, msg));
+                },
+            }
+        }
+
+        if let Some(file_name) = Self::EOP_FILES.iter().find(|file| Path::new(file).is_file()) {
+            match EopTable::new(file_name) {
+                Ok(eop) => time_scales.set_eop(Some(eop)),
+                Err(e) => {
+                    let msg = format!("ERROR::TIME: {} {}", file_name, e);
+                    self.push_message(Message::new(MessageType::Error, msg));
+                },
+            }
+        }
+
+        time_scales
+    }
+
     fn visual_mode(&mut self) { 
-        self.input_mode = false;
+        self.input_mode = InputMode::Normal;
         self.buffer.clear();
     }
 
     pub fn on_tick(&mut self) {
+        let time = self.clock.now();
+
         if let Some(ref mut sat) = &mut self.sat {
-            sat.get_trajectory();
-            sat.update_position();
+            let last_error = sat.get_error();
+
+            sat.get_trajectory_at(time);
+
+            // Only notify when the propagation starts failing, not on every tick
+            if let Err(e) = sat.update_position_at(time) {
+                if last_error != Some(e) {
+                    let msg = format!("ERROR::PROPAGATOR: {}", e);
+                    self.push_message(Message::new(MessageType::Error, msg));
+                }
+            }
         }
     }
     
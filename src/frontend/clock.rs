@@ -0,0 +1,158 @@
+use chrono::{DateTime, NaiveDate, NaiveDateTime, TimeDelta, Utc};
+
+// Time shown by the app, runs with the wall clock until it is paused, warped or moved
+pub struct SimulationClock {
+    // Wall clock time and simulated time of the last change, the simulation runs from there
+    real_origin: DateTime<Utc>,
+    sim_origin: DateTime<Utc>,
+    rate: f64,
+    paused: bool,
+    step: usize,
+
+    // Following the wall clock, until any change is made
+    live: bool
+}
+
+impl SimulationClock {
+
+    // Speeds the clock can run at, relative to the wall clock
+    pub const RATES: [f64; 4] = [1.0, 10.0, 100.0, 1000.0];
+
+    // Increments for stepping forward and backward, in seconds
+    pub const STEPS: [i64; 5] = [1, 60, 600, 3600, 86400];
+
+    // Formats accepted when jumping to a date, the time can be left out
+    const DATE_TIME_FORMATS: [&'static str; 4] = ["%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S", "%Y-%m-%d %H:%M", "%Y-%m-%dT%H:%M"];
+    const DATE_FORMAT: &'static str = "%Y-%m-%d";
+
+    pub fn new() -> Self {
+        let now = Utc::now();
+
+        Self {
+            real_origin: now,
+            sim_origin: now,
+            rate: 1.0,
+            paused: false,
+            step: 1,
+            live: true
+        }
+    }
+
+    // Simulated time at this moment
+    pub fn now(&self) -> DateTime<Utc> {
+        self.at(Utc::now())
+    }
+
+    // Simulated time when the wall clock reads the given time
+    pub fn at(&self, real: DateTime<Utc>) -> DateTime<Utc> {
+        if self.paused {
+            return self.sim_origin;
+        }
+
+        let elapsed = real.signed_duration_since(self.real_origin);
+        let microseconds = elapsed.num_microseconds().unwrap_or(i64::MAX) as f64 * self.rate;
+        let delta = TimeDelta::microseconds(microseconds as i64);
+
+        // Past the dates chrono can represent the clock stays at the last one
+        match self.sim_origin.checked_add_signed(delta) {
+            Some(time) => time,
+            None if delta < TimeDelta::zero() => DateTime::<Utc>::MIN_UTC,
+            None => DateTime::<Utc>::MAX_UTC
+        }
+    }
+
+    // Starts counting again from the current simulated time, needed before changing how it runs
+    fn rebase(&mut self) {
+        let now = Utc::now();
+
+        self.sim_origin = self.at(now);
+        self.real_origin = now;
+        self.live = false;
+    }
+
+    pub fn toggle_pause(&mut self) {
+        self.rebase();
+        self.paused = !self.paused;
+    }
+
+    // Goes to the next rate, after the fastest one goes back to real speed
+    pub fn speed_up(&mut self) {
+        let index = Self::RATES.iter().position(|rate| *rate == self.rate).unwrap_or(0);
+
+        self.rebase();
+        self.rate = Self::RATES[(index + 1) % Self::RATES.len()];
+    }
+
+    // Goes to the next step increment, after the largest one goes back to the smallest
+    pub fn next_step(&mut self) {
+        self.step = (self.step + 1) % Self::STEPS.len();
+    }
+
+    // Steps past the dates chrono can represent are ignored
+    pub fn step_forward(&mut self) {
+        self.rebase();
+
+        if let Some(time) = self.sim_origin.checked_add_signed(self.get_step()) {
+            self.sim_origin = time;
+        }
+    }
+
+    pub fn step_backward(&mut self) {
+        self.rebase();
+
+        if let Some(time) = self.sim_origin.checked_sub_signed(self.get_step()) {
+            self.sim_origin = time;
+        }
+    }
+
+    pub fn jump_to(&mut self, time: DateTime<Utc>) {
+        self.real_origin = Utc::now();
+        self.sim_origin = time;
+        self.live = false;
+    }
+
+    // Back to the wall clock at real speed
+    pub fn reset(&mut self) {
+        *self = Self {
+            step: self.step,
+            ..Self::new()
+        };
+    }
+
+    // UTC date typed by the user as "YYYY-MM-DD [HH:MM[:SS]]"
+    pub fn parse_date(text: &str) -> Option<DateTime<Utc>> {
+        let text = text.trim();
+
+        let date_time = Self::DATE_TIME_FORMATS
+            .iter()
+            .find_map(|format| NaiveDateTime::parse_from_str(text, format).ok())
+            .or_else(|| NaiveDate::parse_from_str(text, Self::DATE_FORMAT).ok()?.and_hms_opt(0, 0, 0))?;
+
+        Some(date_time.and_utc())
+    }
+
+    // Getters
+
+    pub fn get_rate(&self) -> f64 {
+        self.rate
+    }
+
+    pub fn get_step(&self) -> TimeDelta {
+        TimeDelta::seconds(Self::STEPS[self.step])
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    // Whether the clock shows the wall clock time
+    pub fn is_live(&self) -> bool {
+        self.live
+    }
+}
+
+impl Default for SimulationClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
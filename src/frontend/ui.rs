@@ -1,14 +1,17 @@
-use crate::App;
-use crate::PositionVector;
-use crate::Satellite;
+use crate::backend::satellite::Satellite;
+use crate::backend::propagator::PropagationError;
 use crate::frontend::app::{
+    App,
+    InputMode,
     MessageType,
     Message
 };
 
+use chrono::TimeDelta;
+
 use ratatui::{
     style::{Style, Color, Modifier},
-    widgets::{Borders, Block, Paragraph, Tabs, Clear, List},
+    widgets::{Borders, Block, Paragraph, Tabs, Clear, List, Wrap},
     widgets::canvas::{Canvas, Points, Circle, Line, MapResolution, Map, Context},
     prelude::{Constraint, Rect, Direction, Layout, Stylize},
     text::Span,
@@ -18,8 +21,9 @@ use ratatui::{
 };
 
 const USAGE: &str = "c - Set user Coordinates | Enter - Clear popups | q - Quit";
+const CLOCK_USAGE: &str = "Space - Pause | f - Speed up | . , - Step forward/backward | s - Change step | t - Jump to date | r - Real time";
 
-const POPUP_WIDTH: u16 = 55;
+const POPUP_WIDTH: u16 = 70;
 const POPUP_HEIGHT: u16 = 3;
 
 const DARK_RED: Color = Color::Rgb(150, 24, 16);
@@ -35,7 +39,7 @@ pub fn draw(frame: &mut Frame, app: &mut App) {
         Constraint::Min(0),
         Constraint::Length(1),
     ]);
-    let [title_bar, tab, bottom_bar] = vertical.areas(frame.area()); // TODO: Do something with
+    let [title_bar, tab, _bottom_bar] = vertical.areas(frame.area()); // TODO: Do something with
                                                                      // bottom_bar
  
     draw_title_bar(frame, app, title_bar);
@@ -51,7 +55,7 @@ pub fn draw(frame: &mut Frame, app: &mut App) {
         };
     }
 
-    if app.input_mode {
+    if app.input_mode != InputMode::Normal {
 
         // The middle of the frame
         let x = (frame.area().width - POPUP_WIDTH) / 2;
@@ -59,8 +63,13 @@ pub fn draw(frame: &mut Frame, app: &mut App) {
 
         let area = Rect::new(x, y, POPUP_WIDTH, POPUP_HEIGHT).clamp(frame.area()); // Clamps rect inside the frame
 
+        let title = match app.input_mode {
+            InputMode::Date => "Jump to UTC date: [YYYY-MM-DD] [HH:MM:SS]",
+            _ => "Set user coordinates: [lat(deg)] [lon(deg)] [alt(km)] [datum]",
+        };
+
         let position_data = Block::default()
-        .title("Set user coordinates: [lat(deg)] [lon(deg)] [alt(km)]")
+        .title(title)
         .borders(Borders::ALL);
     
         let data = Paragraph::new(app.buffer.clone())
@@ -80,7 +89,24 @@ fn draw_title_bar(frame: &mut Frame, app: &App, area: Rect)
     let layout = Layout::horizontal([Constraint::Min(0), Constraint::Length(43)]);
     let [title_area, tabs_area] = layout.areas(area);
 
-    let title = Span::styled(app.title, Style::new()
+    // Show the name of the selected satellite next to the app title
+    let title = match app.get_sat() {
+        Some(sat) => format!("{} - {}", app.title, sat.get_name()),
+        None => app.title.to_string(),
+    };
+
+    // Simulated time and how it runs
+    let rate = if app.clock.is_paused() {
+        String::from("PAUSED")
+    } else if app.clock.is_live() {
+        String::from("LIVE")
+    } else {
+        format!("x{}", app.clock.get_rate())
+    };
+
+    let title = format!("{} | {} UTC {} (step {})", title, app.clock.now().format("%Y-%m-%d %H:%M:%S"), rate, format_step(app.clock.get_step()));
+
+    let title = Span::styled(title, Style::new()
         .fg(WHITE)
         .add_modifier(Modifier::BOLD));
 
@@ -104,6 +130,17 @@ fn draw_title_bar(frame: &mut Frame, app: &App, area: Rect)
     frame.render_widget(tabs, tabs_area);
 }
 
+// Step of the clock in its largest whole unit
+fn format_step(step: TimeDelta) -> String
+{
+    match step.num_seconds() {
+        s if s % 86400 == 0 => format!("{}d", s / 86400),
+        s if s % 3600 == 0 => format!("{}h", s / 3600),
+        s if s % 60 == 0 => format!("{}m", s / 60),
+        s => format!("{}s", s),
+    }
+}
+
 /*
  * Main tabs - Display the data in two different projections and the information used for said
  * projections. 
@@ -178,7 +215,7 @@ fn draw_about_tab(frame: &mut Frame, sat: &Satellite, app: &App, area: Rect)
          .constraints(
              [
                  Constraint::Percentage(85),
-                 Constraint::Percentage(5)
+                 Constraint::Percentage(10)
              ].as_ref()
          )
          .split(area);
@@ -201,6 +238,10 @@ fn draw_about_tab(frame: &mut Frame, sat: &Satellite, app: &App, area: Rect)
             Span::styled("Usage: ", Style::default().fg(Color::Green)),
             Span::styled(USAGE, Style::default().fg(Color::Gray)),
         ]),
+        text::Line::from(vec![
+            Span::styled("Clock: ", Style::default().fg(Color::Green)),
+            Span::styled(CLOCK_USAGE, Style::default().fg(Color::Gray)),
+        ]),
         text::Line::from(vec![
             Span::styled("By Jaime Nazar Anchorena - 2025", Style::default().fg(Color::Yellow)),
         ]),
@@ -225,8 +266,8 @@ fn paint_map(ctx: &mut Context, sat: &Satellite, app: &App)
     ctx.layer();    
      
     ctx.draw(&Circle {
-        x: app.get_usr_geodetic().get_y() * (180.0/core::f64::consts::PI),
-        y: app.get_usr_geodetic().get_x() * (180.0/core::f64::consts::PI),
+        x: app.get_usr_geodetic().get_longitude_degrees(),
+        y: app.get_usr_geodetic().get_latitude_degrees(),
         radius: 1.0,
         color: Color::Red,
     });
@@ -238,11 +279,18 @@ fn paint_map(ctx: &mut Context, sat: &Satellite, app: &App)
         color: Color::Green
     });
 
+    // The last position is not valid if the propagation failed
+    if sat.get_error().is_some() {
+        return;
+    }
+
+    let sat_geodetic = sat.get_geodetic_position();
+
     ctx.layer();    // Go one layer above
                      //
     ctx.draw(&Circle {
-        x: (sat.get_geodetic_position().get_y()* 180.0/3.14159),
-        y: (sat.get_geodetic_position().get_x()* 180.0/3.14159),
+        x: sat_geodetic.get_longitude_degrees(),
+        y: sat_geodetic.get_latitude_degrees(),
         radius: 5.0,
         color: Color::Yellow,
     });
@@ -254,17 +302,31 @@ fn draw_stereographic_coords(frame: &mut Frame, sat: &Satellite, app: &App, area
     .title("Stereographic Coordinates")
     .borders(Borders::ALL);
 
-    // Get Elevation and Azimuth
-    let el_az = get_azimuth_and_elevation(&app.get_usr_geodetic(), &sat.get_geodetic_position());
+    if let Some(error) = sat.get_error() {
+        draw_propagation_error(frame, error, position_data, area);
+        return;
+    }
+
+    // Get Elevation, Azimuth and Range
+    let look_angles = app.get_observer().look_angles(sat.get_ecef_state());
 
     let text = vec![
         text::Line::from(vec![
             Span::from("Azimuth: "),
-            Span::styled(format!("{:.5} deg", (el_az.get_x() * (180.0/core::f64::consts::PI)).to_string()), Style::default().fg(Color::Blue)),
+            Span::styled(format!("{:.5} deg", look_angles.get_azimuth_degrees()), Style::default().fg(Color::Blue)),
         ]),
         text::Line::from(vec![
             Span::from("Elevation: "),
-            Span::styled(format!("{:.5} deg",(el_az.get_y() * (180.0/core::f64::consts::PI)).to_string()), Style::default().fg(Color::Green)),
+            Span::styled(format!("{:.5} deg", look_angles.get_elevation_degrees()), Style::default().fg(Color::Green)),
+        ]),
+        text::Line::from(vec![
+            Span::from("Range: "),
+            Span::styled(format!("{:.3} km", look_angles.range), Style::default().fg(Color::Red)),
+        ]),
+        text::Line::from(vec![
+            // Positive when the satellite moves away
+            Span::from("Range rate: "),
+            Span::styled(format!("{:.4} km/s", look_angles.range_rate), Style::default().fg(Color::Yellow)),
         ])
     ];
 
@@ -284,15 +346,19 @@ fn draw_user_coords(frame: &mut Frame, app: &App, area: Rect)
     let text = vec![
         text::Line::from(vec![
             Span::from("Latitude: "),
-            Span::styled(format!("{:.5} deg", (app.get_usr_geodetic().get_x() * (180.0/core::f64::consts::PI)).to_string()), Style::default().fg(Color::Blue)),
+            Span::styled(format!("{:.5} deg", app.get_usr_geodetic().get_latitude_degrees()), Style::default().fg(Color::Blue)),
         ]),
         text::Line::from(vec![
             Span::from("Longitude: "),
-            Span::styled(format!("{:.5} deg",(app.get_usr_geodetic().get_y() * (180.0/core::f64::consts::PI)).to_string()), Style::default().fg(Color::Green)),
+            Span::styled(format!("{:.5} deg", app.get_usr_geodetic().get_longitude_degrees()), Style::default().fg(Color::Green)),
         ]),
         text::Line::from(vec![
             Span::from("Altitude: "),
-            Span::styled(format!("{:.5} km", app.get_usr_geodetic().get_z().to_string()), Style::default().fg(Color::Red)),
+            Span::styled(format!("{:.5} km", app.get_usr_geodetic().altitude), Style::default().fg(Color::Red)),
+        ]),
+        text::Line::from(vec![
+            Span::from("Datum: "),
+            Span::styled(app.get_usr_datum().name(), Style::default().fg(Color::Yellow)),
         ])
     ];
 
@@ -308,19 +374,26 @@ fn draw_sat_coords(frame: &mut Frame, sat: &Satellite, area: Rect) // Repeated c
     let position_data = Block::default()
         .title("Satellite Coordinates")
         .borders(Borders::ALL);
+
+    if let Some(error) = sat.get_error() {
+        draw_propagation_error(frame, error, position_data, area);
+        return;
+    }
  
+    let sat_geodetic = sat.get_geodetic_position();
+
     let text = vec![
         text::Line::from(vec![
             Span::from("Altitude: "),
-            Span::styled(format!("{:.5} km", sat.get_geodetic_position().get_z().to_string()), Style::default().fg(Color::Red)),
+            Span::styled(format!("{:.5} km", sat_geodetic.altitude), Style::default().fg(Color::Red)),
         ]),
         text::Line::from(vec![
             Span::from("Longitude: "),
-            Span::styled(format!("{:.5} deg", (sat.get_geodetic_position().get_y() * (180.0/core::f64::consts::PI)).to_string()), Style::default().fg(Color::Green)),
+            Span::styled(format!("{:.5} deg", sat_geodetic.get_longitude_degrees()), Style::default().fg(Color::Green)),
         ]),
         text::Line::from(vec![
             Span::from("Latitude: "),
-            Span::styled(format!("{:.5} deg", (sat.get_geodetic_position().get_x() * (180.0/core::f64::consts::PI)).to_string()), Style::default().fg(Color::Blue)),
+            Span::styled(format!("{:.5} deg", sat_geodetic.get_latitude_degrees()), Style::default().fg(Color::Blue)),
         ])
     ];
  
@@ -331,6 +404,22 @@ fn draw_sat_coords(frame: &mut Frame, sat: &Satellite, area: Rect) // Repeated c
     frame.render_widget(data, area); 
 }
 
+// Shown instead of the coordinates when the satellite position could not be propagated
+fn draw_propagation_error(frame: &mut Frame, error: PropagationError, block: Block, area: Rect)
+{
+    let text = vec![
+        text::Line::from(Span::styled("Propagation failed: ", Style::default().fg(Color::Red))),
+        text::Line::from(Span::from(error.to_string())),
+    ];
+
+    let data = Paragraph::new(text)
+        .block(block)
+        .wrap(Wrap { trim: true })
+        .style(Style::default().fg(Color::White));
+
+    frame.render_widget(data, area);
+}
+
 fn draw_tle_data(frame: &mut Frame, sat: &Satellite, area: Rect)
 {
     let tle_data = Block::default()
@@ -350,10 +439,18 @@ fn draw_tle_data(frame: &mut Frame, sat: &Satellite, area: Rect)
             Span::from("Launch Year: "),
             Span::styled(sat.get_tle().get_launch_year().to_string(), Style::default().fg(Color::Yellow)),
         ]),
+        text::Line::from(vec![
+            Span::from("Launch Number: "),
+            Span::styled(sat.get_tle().get_launch_number().to_string(), Style::default().fg(Color::Yellow)),
+        ]),
         text::Line::from(vec![
             Span::from("Launch Piece: "),
             Span::styled(sat.get_tle().get_launch_piece().to_string(), Style::default().fg(Color::Yellow)),
         ]),
+        text::Line::from(vec![
+            Span::from("Element Set Number: "),
+            Span::styled(sat.get_tle().get_element_set_number().to_string(), Style::default().fg(Color::Yellow)),
+        ]),
         // TODO: change color of smth to mark them as different
         text::Line::from(vec![
             Span::from("Epoch Year: "),
@@ -363,10 +460,18 @@ fn draw_tle_data(frame: &mut Frame, sat: &Satellite, area: Rect)
             Span::from("Epoch Day of Year: "),
             Span::styled(format!("{:.5}", sat.get_tle().get_epoch_day().to_string()), Style::default().fg(Color::Yellow)),
         ]),
+        text::Line::from(vec![
+            Span::from("Epoch: "),
+            Span::styled(sat.get_tle().get_epoch().format("%Y-%m-%d %H:%M:%S%.6f UTC").to_string(), Style::default().fg(Color::Yellow)),
+        ]),
         text::Line::from(vec![
             Span::from("Ballistic Coefficient: "),
             Span::styled(format!("{:.10}", sat.get_tle().get_ballistic_coefficient()).to_string(), Style::default().fg(Color::Yellow)),
         ]),
+        text::Line::from(vec![
+            Span::from("Mean Motion 2nd Derivative: "),
+            Span::styled(format!("{:.10}", sat.get_tle().get_mean_motion_ddot()), Style::default().fg(Color::Yellow)),
+        ]),
         text::Line::from(vec![
             Span::from("Drag Term: "),
             Span::styled(format!("{:.10}", sat.get_tle().get_drag_term().to_string()), Style::default().fg(Color::Yellow)),
@@ -456,16 +561,22 @@ fn paint_azimuth(ctx: &mut Context, sat: &Satellite, app: &App)
     //     coords: &v,
     //     color: Color::Green
     // });
+
+    // The last position is not valid if the propagation failed
+    if sat.get_error().is_some() {
+        return;
+    }
+
     ctx.layer();
 
     // Get Elevation and Azimuth
-    let el_az = get_azimuth_and_elevation(&app.get_usr_geodetic(), &sat.get_geodetic_position());
+    let look_angles = app.get_observer().look_angles(sat.get_ecef_state());
     
-    let p = 90.0 - (el_az.get_y()*(180.0/core::f64::consts::PI));
+    let p = 90.0 - look_angles.get_elevation_degrees();
 
     ctx.draw(&Circle {
-        x: -p*el_az.get_x().sin(),
-        y: p*el_az.get_x().cos(),
+        x: -p*look_angles.azimuth.sin(),
+        y: p*look_angles.azimuth.cos(),
         radius: 5.0,
         color: Color::Blue,
     });
@@ -479,7 +590,7 @@ fn draw_tle_options(frame: &mut Frame, app: &App, area: Rect)
         .borders(Borders::ALL);
 
 
-    let list = List::new(app.tle_list.items.clone())
+    let list = List::new(app.tle_list.items.iter().map(|tle| tle.get_display_name()))
         .block(block)
         .style(Style::new().white())
         .highlight_style(Style::new()
@@ -531,12 +642,3 @@ fn draw_popup_message(frame: &mut Frame, msg: &Message) {
 
 }
 
-fn get_azimuth_and_elevation(usr_geodetic: &PositionVector, sat_geodetic: &PositionVector) -> PositionVector {
-    // Get Elevation and Azimuth
-    let usr_ecef = usr_geodetic.geodetic_to_ecef();
-    let sat_ecef = sat_geodetic.geodetic_to_ecef();
-    
-    let p_enu = PositionVector::ecef_to_enu(&usr_ecef, &sat_ecef);
-    
-    p_enu.enu_to_azimuth_and_elevation()
-}
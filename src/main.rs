@@ -1,10 +1,5 @@
-mod backend;
-mod frontend;
-
-use frontend::{app::App, ui};
-
-use backend::satellite::Satellite;
-use backend::vector::PositionVector;
+use sequisat::backend::tle::ValidationMode;
+use sequisat::frontend::{app::{App, InputMode}, ui};
 
 use std::{
     io,
@@ -19,11 +14,12 @@ use ratatui::{
     Terminal,
 };
 
-pub fn run(tick_rate: Duration) -> io::Result<()> {
+pub fn run(tick_rate: Duration, validation: ValidationMode) -> io::Result<()> {
 
     let mut terminal = ratatui::init();
     // create app and run it
     let mut app = App::new("SEQUISAT");
+    app.validation = validation;
     app.initialize();
 
     let app_result = run_app(&mut terminal, app, tick_rate);
@@ -48,12 +44,12 @@ fn run_app<B: Backend>(
             if let Event::Key(key) = event::read()? {
                 if key.kind == KeyEventKind::Press {
                     match (app.input_mode, key.code) {
-                        (false, KeyCode::Left | KeyCode::Char('h')) => app.on_left(),
-                        (false, KeyCode::Up | KeyCode::Char('k')) => app.on_up(),
-                        (false, KeyCode::Right | KeyCode::Char('l')) => app.on_right(),
-                        (false, KeyCode::Down | KeyCode::Char('j')) => app.on_down(),
-                        (false, _) => app.on_key_normal(key.code),
-                        (true, _) => app.on_key_input(key.code)
+                        (InputMode::Normal, KeyCode::Left | KeyCode::Char('h')) => app.on_left(),
+                        (InputMode::Normal, KeyCode::Up | KeyCode::Char('k')) => app.on_up(),
+                        (InputMode::Normal, KeyCode::Right | KeyCode::Char('l')) => app.on_right(),
+                        (InputMode::Normal, KeyCode::Down | KeyCode::Char('j')) => app.on_down(),
+                        (InputMode::Normal, _) => app.on_key_normal(key.code),
+                        (_, _) => app.on_key_input(key.code)
                     }
                 }
             }
@@ -71,8 +67,15 @@ fn run_app<B: Backend>(
 fn main() -> io::Result<()> {
     
     let tick_rate: Duration = Duration::from_millis(100);
+
+    // Element sets with format errors are loaded anyway, showing the errors as warnings
+    let validation = if std::env::args().any(|arg| arg == "--lenient") {
+        ValidationMode::Lenient
+    } else {
+        ValidationMode::Strict
+    };
     
-    run(tick_rate)?;
+    run(tick_rate, validation)?;
 
     Ok(())
 }
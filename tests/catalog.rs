@@ -0,0 +1,58 @@
+use sequisat::backend::catalog::{Catalog, Position};
+
+// Excerpt of a Celestrak group file, the second element set has a corrupted inclination
+const GROUP_FILE: &str = "\
+NOAA 15
+1 25338U 98030A   25060.54009532  .00000428  00000-0  19397-3 0  9998
+2 25338  98.5469  88.0293 0009156 294.2635  65.7589 14.26897559394132
+NOAA 18
+1 28654U 05018A   25060.53627539  .00000440  00000-0  25676-3 0  9999
+2 28654  9x.8487 141.1226 0014866  56.7021 303.5573 14.13529858 19534
+
+1 33591U 09005A   25060.59715034  .00000391  00000-0  23254-3 0  9994
+2 33591  99.0103 124.6938 0012767 286.7911  73.1860 14.13298055827724
+";
+
+#[test]
+fn every_element_set_is_loaded() {
+    let catalog = Catalog::parse(GROUP_FILE);
+
+    let catalog_numbers: Vec<i32> = catalog.get_entries().iter().map(|tle| tle.get_catalog_number()).collect();
+
+    assert_eq!(catalog_numbers, vec![25338, 33591]);
+    assert_eq!(catalog.get_entries()[0].get_name(), Some("NOAA 15"));
+    assert_eq!(catalog.get_entries()[1].get_name(), None);
+}
+
+#[test]
+fn failures_are_reported_per_entry() {
+    let catalog = Catalog::parse(GROUP_FILE);
+
+    assert_eq!(catalog.get_errors().len(), 1);
+    assert_eq!(catalog.get_errors()[0].position, Position::Line(4));
+}
+
+#[test]
+fn incomplete_element_set_does_not_swallow_the_next_one() {
+    let catalog = Catalog::parse("\
+ISS (ZARYA)
+1 25544U 98067A   24354.78715486  .00013667  00000-0  24028-3 0  9996
+NOAA 19
+1 33591U 09005A   25060.59715034  .00000391  00000-0  23254-3 0  9994
+2 33591  99.0103 124.6938 0012767 286.7911  73.1860 14.13298055827724
+");
+
+    assert_eq!(catalog.get_entries().len(), 1);
+    assert_eq!(catalog.get_entries()[0].get_name(), Some("NOAA 19"));
+    assert_eq!(catalog.get_errors()[0].position, Position::Line(1));
+}
+
+#[test]
+fn other_text_files_are_not_catalogs() {
+    assert!(Catalog::has_element_sets(GROUP_FILE));
+
+    // Notes and build files have no line 1 followed by a line 2
+    assert!(!Catalog::has_element_sets("Remember to update the TLEs\n1 hour before the pass\n"));
+    assert!(!Catalog::has_element_sets("cmake_minimum_required(VERSION 3.10)\nproject(Tracker)\n"));
+    assert!(!Catalog::has_element_sets("2 25544  51.6389\n1 25544U 98067A\n"));
+}
@@ -0,0 +1,104 @@
+use sequisat::frontend::clock::SimulationClock;
+
+use chrono::{DateTime, TimeDelta, TimeZone, Utc};
+
+#[test]
+fn clock_starts_live() {
+    let clock = SimulationClock::new();
+
+    assert!(clock.is_live());
+    assert!(!clock.is_paused());
+    assert_eq!(clock.get_rate(), 1.0);
+    assert!((clock.now() - Utc::now()).num_seconds().abs() < 1);
+}
+
+#[test]
+fn paused_clock_only_moves_when_stepped() {
+    let time = Utc.with_ymd_and_hms(2024, 12, 19, 18, 53, 30).unwrap();
+    let mut clock = SimulationClock::new();
+
+    clock.jump_to(time);
+    clock.toggle_pause();
+    let paused = clock.now();
+
+    assert!(clock.is_paused());
+    assert_eq!(clock.at(Utc::now() + TimeDelta::hours(1)), paused);
+
+    clock.step_forward();
+    assert_eq!(clock.now(), paused + clock.get_step());
+
+    clock.next_step();
+    clock.step_backward();
+    clock.step_backward();
+    assert_eq!(clock.now(), paused + TimeDelta::minutes(1) - TimeDelta::minutes(20));
+}
+
+#[test]
+fn warped_clock_runs_faster() {
+    let mut clock = SimulationClock::new();
+
+    clock.speed_up();
+    clock.speed_up();
+    assert_eq!(clock.get_rate(), 100.0);
+
+    let real = Utc::now();
+    let elapsed = clock.at(real + TimeDelta::seconds(6)) - clock.at(real);
+    assert_eq!(elapsed, TimeDelta::minutes(10));
+
+    // After the fastest rate it goes back to real speed
+    clock.speed_up();
+    clock.speed_up();
+    assert_eq!(clock.get_rate(), 1.0);
+}
+
+#[test]
+fn reset_goes_back_to_real_time() {
+    let mut clock = SimulationClock::new();
+
+    clock.jump_to(Utc.with_ymd_and_hms(2000, 1, 1, 12, 0, 0).unwrap());
+    clock.speed_up();
+    clock.toggle_pause();
+    assert!(!clock.is_live());
+
+    clock.reset();
+    assert!(clock.is_live());
+    assert_eq!(clock.get_rate(), 1.0);
+    assert!((clock.now() - Utc::now()).num_seconds().abs() < 1);
+}
+
+#[test]
+fn typed_dates() {
+    let time = Utc.with_ymd_and_hms(2025, 3, 1, 14, 20, 5).unwrap();
+
+    assert_eq!(SimulationClock::parse_date("2025-03-01 14:20:05"), Some(time));
+    assert_eq!(SimulationClock::parse_date("2025-03-01T14:20:05"), Some(time));
+    assert_eq!(SimulationClock::parse_date(" 2025-03-01 14:20 "), Some(time - TimeDelta::seconds(5)));
+    assert_eq!(SimulationClock::parse_date("2025-03-01"), Utc.with_ymd_and_hms(2025, 3, 1, 0, 0, 0).single());
+    assert_eq!(SimulationClock::parse_date("01/03/2025"), None);
+}
+
+#[test]
+fn steps_past_the_last_representable_date_are_ignored() {
+    let mut clock = SimulationClock::new();
+
+    clock.jump_to(DateTime::<Utc>::MAX_UTC);
+    clock.toggle_pause();
+    let last = clock.now();
+
+    clock.step_forward();
+    assert_eq!(clock.now(), last);
+
+    clock.jump_to(DateTime::<Utc>::MIN_UTC);
+    clock.step_backward();
+    assert_eq!(clock.now(), DateTime::<Utc>::MIN_UTC);
+}
+
+#[test]
+fn warped_clock_stops_at_the_last_representable_date() {
+    let mut clock = SimulationClock::new();
+
+    clock.jump_to(DateTime::<Utc>::MAX_UTC - TimeDelta::seconds(1));
+    clock.speed_up();
+
+    assert_eq!(clock.at(Utc::now() + TimeDelta::hours(1)), DateTime::<Utc>::MAX_UTC);
+}
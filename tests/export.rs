@@ -0,0 +1,70 @@
+use sequisat::backend::omm::Omm;
+use sequisat::backend::tle::TLE;
+
+// Element sets that must be written back exactly as they were read
+const ELEMENT_SETS: [[&str; 2]; 4] = [
+    [
+        "1 25544U 98067A   24354.78715486  .00013667  00000-0  24028-3 0  9996",
+        "2 25544  51.6389 115.8117 0007806 357.7173 156.9527 15.50767798487337",
+    ],
+    [
+        "1 48275U 21035ABC 21115.24063657  .00010372 -11606-4  12082-3 0  9998",
+        "2 48275  41.4714 210.1093 0002208 274.3498 215.3463 15.61178962123459",
+    ],
+    [
+        "1 A0001U 21035A   21115.24063657  .00010372  00000-0  12082-3 0  9994",
+        "2 A0001  41.4714 210.1093 0002208 274.3498 215.3463 15.61178962123454",
+    ],
+    [
+        "1 28350U 04020A   06167.21788666  .16154492  76267-5  18678-3 0  8894",
+        "2 28350  64.9977 345.6130 0024870 260.7578  99.9590 16.47856722116490",
+    ],
+];
+
+#[test]
+fn tle_lines_are_written_back_unchanged() {
+    for [line_1, line_2] in ELEMENT_SETS {
+        let tle = TLE::parse(&format!("{}\n{}", line_1, line_2)).expect("Failed to parse the TLE");
+
+        assert_eq!(tle.to_lines(), Ok([line_1.to_string(), line_2.to_string()]));
+    }
+}
+
+#[test]
+fn name_line_is_written_when_present() {
+    let [line_1, line_2] = ELEMENT_SETS[0];
+    let text = format!("ISS (ZARYA)\n{}\n{}\n", line_1, line_2);
+
+    let tle = TLE::parse(&text).expect("Failed to parse the TLE");
+
+    assert_eq!(tle.to_text(), Ok(text));
+}
+
+#[test]
+fn omm_round_trip_keeps_the_elements() {
+    for [line_1, line_2] in ELEMENT_SETS {
+        let tle = TLE::parse(&format!("{}\n{}", line_1, line_2)).expect("Failed to parse the TLE");
+        let omm = Omm::from_tle(&tle).expect("Failed to convert the TLE");
+
+        for contents in [omm.to_kvn(), omm.to_xml()] {
+            let messages = Omm::parse(&contents).unwrap_or_else(|e| panic!("Failed to read back {}: {}", contents, e));
+            let read_back = messages[0].to_tle().expect("Failed to convert the OMM");
+
+            assert_eq!(read_back.to_lines(), Ok([line_1.to_string(), line_2.to_string()]), "{}", contents);
+        }
+    }
+}
+
+#[test]
+fn omm_object_name_is_not_made_up() {
+    let [line_1, line_2] = ELEMENT_SETS[0];
+
+    let unnamed = TLE::parse(&format!("{}\n{}", line_1, line_2)).expect("Failed to parse the TLE");
+    let omm = Omm::from_tle(&unnamed).expect("Failed to convert the TLE");
+
+    assert_eq!(omm.get("OBJECT_NAME"), Some("UNKNOWN"));
+    assert_eq!(omm.to_tle().expect("Failed to convert the OMM").get_name(), None);
+
+    let named = TLE::parse(&format!("ISS (ZARYA)\n{}\n{}", line_1, line_2)).expect("Failed to parse the TLE");
+    assert_eq!(Omm::from_tle(&named).expect("Failed to convert the TLE").get("OBJECT_NAME"), Some("ISS (ZARYA)"));
+}
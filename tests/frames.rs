@@ -0,0 +1,65 @@
+use sequisat::backend::coordinates::Teme;
+use sequisat::backend::frames::{self, EarthOrientation};
+use sequisat::backend::time::JulianDate;
+use sequisat::backend::timescale::TimeScales;
+use sequisat::backend::vector::PositionVector;
+
+use chrono::{DateTime, TimeDelta, TimeZone, Utc};
+
+// Example of Vallado et al., "Revisiting Spacetrack Report #3" (2006): 2004-04-06 07:51:28.386009 UTC,
+// UT1-UTC = -0.4399619 s, TAI-UTC = 32 s, xp = -0.140682", yp = 0.333309"
+fn example_time() -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2004, 4, 6, 7, 51, 28).unwrap() + TimeDelta::microseconds(386009)
+}
+
+fn example_state() -> Teme {
+    Teme::new(
+        PositionVector::new(5094.18016210, 6127.64465950, 6380.34453270),
+        PositionVector::new(-4.746131487, 0.785818041, 5.531931288),
+    )
+}
+
+fn arcseconds(value: f64) -> f64 {
+    (value / 3600.0).to_radians()
+}
+
+fn assert_close(vector: &PositionVector, expected: [f64; 3], tolerance: f64) {
+    let values = [vector.get_x(), vector.get_y(), vector.get_z()];
+
+    for (value, expected) in values.iter().zip(expected) {
+        assert!((value - expected).abs() < tolerance, "{} instead of {}", value, expected);
+    }
+}
+
+#[test]
+fn teme_to_earth_fixed() {
+    let ut1 = JulianDate::from_datetime(example_time()).add_days(-0.4399619 / 86400.0);
+
+    let pef = frames::teme_to_pef(&example_state(), &ut1);
+    assert_close(pef.get_position(), [-1033.4750313, 7901.3055856, 6380.3445327], 1.0e-5);
+
+    let itrf = frames::pef_to_itrf(&pef, (arcseconds(-0.140682), arcseconds(0.333309)));
+    assert_close(itrf.get_position(), [-1033.4793830, 7901.2952754, 6380.3565958], 1.0e-5);
+    // The reference slows the rotation by the length of day, 0.01 mm/s here
+    assert_close(itrf.get_velocity(), [-3.225636520, -2.872451450, 5.531924446], 1.0e-7);
+}
+
+#[test]
+fn teme_to_gcrf() {
+    let tt = JulianDate::from_datetime(example_time()).add_days((32.0 + 32.184) / 86400.0);
+
+    // The reference applies the EOP corrections to the nutation, left out here, so it only agrees to the meter
+    let gcrf = frames::teme_to_gcrf(&example_state(), &tt);
+    assert_close(gcrf.get_position(), [5102.508958, 6123.011401, 6378.136928], 1.0e-3);
+}
+
+#[test]
+fn orientation_without_eop_data() {
+    let orientation = EarthOrientation::new(example_time(), &TimeScales::default());
+    let itrf = orientation.teme_to_itrf(&example_state());
+
+    // Without polar motion the earth fixed frame is the PEF, with UT1 taken as UTC
+    assert_eq!(orientation.get_polar_motion(), None);
+    assert_eq!(itrf.get_position().get_z(), example_state().get_position().get_z());
+    assert_close(itrf.get_position(), [-1033.4750313, 7901.3055856, 6380.3445327], 0.5);
+}
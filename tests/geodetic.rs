@@ -0,0 +1,117 @@
+use sequisat::backend::coordinates::{Ecef, Geodetic};
+use sequisat::backend::ellipsoid::Ellipsoid;
+use sequisat::backend::vector::PositionVector;
+
+const LATITUDES: [f64; 9] = [-90.0, -89.999, -60.0, -45.0, -0.001, 0.0, 30.0, 51.6, 90.0];
+const LONGITUDES: [f64; 5] = [-180.0, -58.38, 0.0, 90.0, 179.9];
+const ALTITUDES: [f64; 6] = [-5.0, 0.0, 0.8, 420.0, 20200.0, 35786.0];
+
+fn at_rest(x: f64, y: f64, z: f64) -> Ecef {
+    Ecef::new(PositionVector::new(x, y, z), PositionVector::new(0.0, 0.0, 0.0))
+}
+
+fn assert_round_trip(ellipsoid: &Ellipsoid) {
+    for lat in LATITUDES {
+        for lon in LONGITUDES {
+            for alt in ALTITUDES {
+                let geodetic = Geodetic::from_degrees(lat, lon, alt);
+                let back = geodetic.to_ecef(ellipsoid).to_geodetic(ellipsoid);
+
+                assert!((back.latitude - geodetic.latitude).abs() < 1.0e-11, "latitude of {} {} {}: {}", lat, lon, alt, back.get_latitude_degrees());
+                assert!((back.altitude - alt).abs() < 1.0e-7, "altitude of {} {} {}: {}", lat, lon, alt, back.altitude);
+
+                // The longitude is undefined on the poles
+                if lat.abs() != 90.0 {
+                    let difference = (back.longitude - geodetic.longitude + std::f64::consts::PI).rem_euclid(2.0 * std::f64::consts::PI) - std::f64::consts::PI;
+                    assert!(difference.abs() < 1.0e-11, "longitude of {} {} {}", lat, lon, alt);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn geodetic_round_trip_on_every_ellipsoid() {
+    for ellipsoid in Ellipsoid::ALL {
+        assert_round_trip(&ellipsoid);
+    }
+}
+
+#[test]
+fn ellipsoid_constants() {
+    assert!((Ellipsoid::WGS84.semi_minor_axis() - 6356.752314245).abs() < 1.0e-9);
+    assert!((Ellipsoid::GRS80.semi_minor_axis() - 6356.752314140).abs() < 1.0e-9);
+    assert_eq!(Ellipsoid::Spherical.semi_minor_axis(), Ellipsoid::Spherical.semi_major_axis());
+
+    assert_eq!(Ellipsoid::from_name("wgs72"), Some(Ellipsoid::WGS72));
+    assert_eq!(Ellipsoid::from_name("GRS-80"), Some(Ellipsoid::GRS80));
+    assert_eq!(Ellipsoid::from_name("spherical"), Some(Ellipsoid::Spherical));
+    assert_eq!(Ellipsoid::from_name("NAD27"), None);
+}
+
+#[test]
+fn datums_place_the_same_point_differently() {
+    let point = Geodetic::from_degrees(45.0, 0.0, 0.0);
+
+    let wgs84 = point.to_ecef(&Ellipsoid::WGS84).position;
+    let wgs72 = point.to_ecef(&Ellipsoid::WGS72).position;
+    let shift = ((wgs84.get_x() - wgs72.get_x()).powi(2) + (wgs84.get_z() - wgs72.get_z()).powi(2)).sqrt();
+
+    // Around 2 m apart
+    assert!(shift > 1.0e-3 && shift < 5.0e-3, "{}", shift);
+
+    // Over a sphere geodetic and geocentric latitudes are the same
+    let sphere = point.to_ecef(&Ellipsoid::Spherical).position;
+    assert!((sphere.get_z().atan2(sphere.get_x()) - point.latitude).abs() < 1.0e-15);
+}
+
+#[test]
+fn points_on_the_axes() {
+    let wgs84 = Ellipsoid::WGS84;
+
+    let equator = at_rest(wgs84.semi_major_axis() + 400.0, 0.0, 0.0).to_geodetic(&wgs84);
+    assert_eq!(equator.latitude, 0.0);
+    assert!((equator.altitude - 400.0).abs() < 1.0e-9);
+
+    let pole = at_rest(0.0, 0.0, -(wgs84.semi_minor_axis() + 400.0)).to_geodetic(&wgs84);
+    assert!((pole.latitude + std::f64::consts::FRAC_PI_2).abs() < 1.0e-12);
+    assert!((pole.altitude - 400.0).abs() < 1.0e-9);
+}
+
+#[test]
+fn geodetic_latitude_is_not_geocentric() {
+    // At 45 deg of geodetic latitude the geocentric one is about 0.19 deg lower
+    let ecef = Geodetic::from_degrees(45.0, 0.0, 0.0).to_ecef(&Ellipsoid::WGS84);
+    let geocentric = ecef.position.get_z().atan2(ecef.position.get_x()).to_degrees();
+
+    assert!((45.0 - geocentric - 0.1924).abs() < 1.0e-3, "{}", geocentric);
+    assert!((ecef.to_geodetic(&Ellipsoid::WGS84).get_latitude_degrees() - 45.0).abs() < 1.0e-10);
+}
+
+#[test]
+fn look_angles_of_points_around_the_observer() {
+    let wgs84 = Ellipsoid::WGS84;
+    let observer = Geodetic::from_degrees(-34.6036, -58.3816, 0.025);
+
+    // Straight above the observer
+    let zenith = Geodetic::new(observer.latitude, observer.longitude, 420.0).to_ecef(&wgs84);
+    let look = zenith.to_enu(&observer, &wgs84).to_look_angles();
+
+    assert!((look.get_elevation_degrees() - 90.0).abs() < 1.0e-6, "{}", look.get_elevation_degrees());
+    assert!((look.range - 419.975).abs() < 1.0e-9, "{}", look.range);
+
+    // On the horizon, a degree of latitude to the north and to the south (the horizon dips with the curvature)
+    let north = Geodetic::from_degrees(-33.6036, -58.3816, 0.025).to_ecef(&wgs84).to_enu(&observer, &wgs84).to_look_angles();
+    assert!(north.azimuth.abs() < 1.0e-9 || (north.get_azimuth_degrees() - 360.0).abs() < 1.0e-9, "{}", north.get_azimuth_degrees());
+    assert!(north.elevation < 0.0);
+
+    let south = Geodetic::from_degrees(-35.6036, -58.3816, 0.025).to_ecef(&wgs84).to_enu(&observer, &wgs84).to_look_angles();
+    assert!((south.get_azimuth_degrees() - 180.0).abs() < 1.0e-9, "{}", south.get_azimuth_degrees());
+
+    // To the east the azimuth is close to 90 deg, never negative
+    let east = Geodetic::from_degrees(-34.6036, -57.3816, 0.025).to_ecef(&wgs84).to_enu(&observer, &wgs84).to_look_angles();
+    assert!((east.get_azimuth_degrees() - 90.0).abs() < 1.0, "{}", east.get_azimuth_degrees());
+
+    let west = Geodetic::from_degrees(-34.6036, -59.3816, 0.025).to_ecef(&wgs84).to_enu(&observer, &wgs84).to_look_angles();
+    assert!((west.get_azimuth_degrees() - 270.0).abs() < 1.0, "{}", west.get_azimuth_degrees());
+}
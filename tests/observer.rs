@@ -0,0 +1,87 @@
+use sequisat::backend::coordinates::{Ecef, Geodetic, LookAngles};
+use sequisat::backend::ellipsoid::Ellipsoid;
+use sequisat::backend::observer::Observer;
+use sequisat::backend::satellite::Satellite;
+use sequisat::backend::tle::TLE;
+use sequisat::backend::vector::PositionVector;
+
+use chrono::{DateTime, TimeDelta, TimeZone, Utc};
+
+const ISS: &str = "\
+ISS (ZARYA)
+1 25544U 98067A   24354.78715486  .00013667  00000-0  24028-3 0  9996
+2 25544  51.6389 115.8117 0007806 357.7173 156.9527 15.50767798487337";
+
+fn buenos_aires() -> Observer {
+    Observer::new(Geodetic::from_degrees(-34.6036, -58.3816, 0.025), Ellipsoid::WGS84)
+}
+
+fn look_at(sat: &mut Satellite, observer: &Observer, time: DateTime<Utc>) -> LookAngles {
+    sat.update_position_at(time).expect("Failed to propagate");
+
+    observer.look_angles(sat.get_ecef_state())
+}
+
+#[test]
+fn range_is_the_distance_to_the_satellite() {
+    let mut sat = Satellite::new(TLE::parse(ISS).expect("Failed to parse the TLE"));
+    let observer = buenos_aires();
+
+    let look = look_at(&mut sat, &observer, Utc.with_ymd_and_hms(2024, 12, 21, 12, 30, 0).unwrap());
+    let distance = (*sat.get_ecef_position() - observer.get_ecef().position).norm();
+
+    assert!((look.range - distance).abs() < 1.0e-9, "{} instead of {}", look.range, distance);
+}
+
+#[test]
+fn rates_match_the_change_of_the_angles() {
+    let mut sat = Satellite::new(TLE::parse(ISS).expect("Failed to parse the TLE"));
+    let observer = buenos_aires();
+
+    for hour in 0..24 {
+        let time = Utc.with_ymd_and_hms(2024, 12, 21, hour, 0, 0).unwrap();
+        let half_second = TimeDelta::milliseconds(500);
+
+        let look = look_at(&mut sat, &observer, time);
+        let before = look_at(&mut sat, &observer, time - half_second);
+        let after = look_at(&mut sat, &observer, time + half_second);
+
+        // Central differences over a second, the azimuth is wrapped to [-π, π). The velocity of SGP4 is not the
+        // exact derivative of its positions, they only agree to a few cm/s
+        let azimuth_change = (after.azimuth - before.azimuth + std::f64::consts::PI).rem_euclid(2.0 * std::f64::consts::PI) - std::f64::consts::PI;
+
+        assert!((look.range_rate - (after.range - before.range)).abs() < 5.0e-5, "range rate {} instead of {} at {}h", look.range_rate, after.range - before.range, hour);
+        let azimuth_rate = look.azimuth_rate.expect("No azimuth rate");
+        let elevation_rate = look.elevation_rate.expect("No elevation rate");
+
+        assert!((azimuth_rate - azimuth_change).abs() < 1.0e-8, "azimuth rate {} at {}h", azimuth_rate, hour);
+        assert!((elevation_rate - (after.elevation - before.elevation)).abs() < 1.0e-8, "elevation rate {} at {}h", elevation_rate, hour);
+    }
+}
+
+#[test]
+fn targets_at_rest_with_the_earth_do_not_move() {
+    let observer = buenos_aires();
+
+    // Over the equator at the longitude of the observer, to the north of it
+    let target = Ecef::new(PositionVector::new(42164.0 * (-58.3816_f64).to_radians().cos(), 42164.0 * (-58.3816_f64).to_radians().sin(), 0.0), PositionVector::new(0.0, 0.0, 0.0));
+    let look = observer.look_angles(&target);
+
+    assert!(look.azimuth.abs() < 1.0e-12, "{}", look.get_azimuth_degrees());
+    assert!(look.elevation > 0.0);
+    assert_eq!((look.range_rate, look.azimuth_rate, look.elevation_rate), (0.0, Some(0.0), Some(0.0)));
+}
+
+#[test]
+fn rates_of_a_target_at_the_zenith() {
+    // Exactly over the equator and the prime meridian, moving east and upwards
+    let observer = Observer::new(Geodetic::new(0.0, 0.0, 0.0), Ellipsoid::WGS84);
+    let target = Ecef::new(PositionVector::new(Ellipsoid::WGS84.semi_major_axis() + 500.0, 0.0, 0.0), PositionVector::new(0.1, 7.5, 0.0));
+
+    let look = observer.look_angles(&target);
+
+    assert_eq!(look.elevation, std::f64::consts::FRAC_PI_2);
+    assert_eq!(look.range, 500.0);
+    assert_eq!(look.range_rate, 0.1);
+    assert_eq!((look.azimuth_rate, look.elevation_rate), (None, None));
+}
@@ -0,0 +1,103 @@
+use sequisat::backend::catalog::{Catalog, Position};
+use sequisat::backend::omm::{Omm, OmmError, OmmFormat};
+use sequisat::backend::tle::{TLE, ValidationMode};
+
+use std::fs;
+
+const OMM_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/data/omm");
+
+// Same element set as the OMM files
+const ISS_TLE: &str = "\
+ISS (ZARYA)
+1 25544U 98067A   24354.78715486  .00013667  00000-0  24028-3 0  9996
+2 25544  51.6389 115.8117 0007806 357.7173 156.9527 15.50767798487337";
+
+fn assert_same_elements(omm: &TLE, tle: &TLE) {
+    assert_eq!(omm.get_name(), tle.get_name());
+    assert_eq!(omm.get_catalog_number(), tle.get_catalog_number());
+    assert_eq!(omm.get_classification(), tle.get_classification());
+    assert_eq!(omm.get_launch_year(), tle.get_launch_year());
+    assert_eq!(omm.get_launch_number(), tle.get_launch_number());
+    assert_eq!(omm.get_launch_piece(), tle.get_launch_piece());
+    assert_eq!(omm.get_epoch_year(), tle.get_epoch_year());
+    assert_eq!(omm.get_element_set_number(), tle.get_element_set_number());
+    assert_eq!(omm.get_revolution_number(), tle.get_revolution_number());
+
+    let pairs = [
+        (omm.get_epoch_day(), tle.get_epoch_day()),
+        (omm.get_ballistic_coefficient(), tle.get_ballistic_coefficient()),
+        (omm.get_mean_motion_ddot(), tle.get_mean_motion_ddot()),
+        (omm.get_drag_term(), tle.get_drag_term()),
+        (omm.get_inclination(), tle.get_inclination()),
+        (omm.get_right_ascension(), tle.get_right_ascension()),
+        (omm.get_eccetricity(), tle.get_eccetricity()),
+        (omm.get_argument_of_perigee(), tle.get_argument_of_perigee()),
+        (omm.get_mean_anomaly(), tle.get_mean_anomaly()),
+        (omm.get_mean_motion(), tle.get_mean_motion()),
+    ];
+
+    for (a, b) in pairs {
+        assert!((a - b).abs() < 1.0e-12, "{} != {}", a, b);
+    }
+}
+
+#[test]
+fn every_format_gives_the_tle_elements() {
+    let tle = TLE::parse(ISS_TLE).expect("Failed to parse the TLE");
+
+    for (file, format) in [("iss.xml", OmmFormat::Xml), ("iss.kvn", OmmFormat::Kvn), ("iss.json", OmmFormat::Json), ("iss.csv", OmmFormat::Csv)] {
+        let contents = fs::read_to_string(format!("{}/{}", OMM_DIR, file)).expect("Failed to read the OMM");
+
+        assert_eq!(OmmFormat::detect(&contents), format, "{}", file);
+
+        let messages = Omm::parse(&contents).unwrap_or_else(|e| panic!("Failed to parse {}: {}", file, e));
+        assert_eq!(messages.len(), 1, "{}", file);
+
+        let omm = messages[0].to_tle().unwrap_or_else(|e| panic!("Failed to convert {}: {}", file, e));
+        assert_same_elements(&omm, &tle);
+    }
+}
+
+#[test]
+fn omm_files_are_loaded_as_catalogs() {
+    let catalog = Catalog::new(&format!("{}/iss.json", OMM_DIR), ValidationMode::Strict).expect("Failed to read the OMM");
+
+    assert_eq!(catalog.get_entries().len(), 1);
+    assert!(catalog.get_errors().is_empty());
+}
+
+#[test]
+fn missing_elements_are_reported_per_message() {
+    let contents = "\
+OBJECT_NAME,EPOCH,MEAN_MOTION,ECCENTRICITY,INCLINATION,RA_OF_ASC_NODE,ARG_OF_PERICENTER,MEAN_ANOMALY
+ISS (ZARYA),2024-12-19T18:53:30.179904,15.50767798,.0007806,51.6389,115.8117,357.7173,156.9527
+\"BROKEN, SAT\",2024-12-19T18:53:30.179904,,.0007806,51.6389,115.8117,357.7173,156.9527
+";
+
+    let catalog = Catalog::parse_omm(contents).expect("Failed to parse the OMM");
+
+    assert_eq!(catalog.get_entries().len(), 1);
+    assert_eq!(catalog.get_errors().len(), 1);
+    assert_eq!(catalog.get_errors()[0].position, Position::Message(2));
+}
+
+#[test]
+fn malformed_object_ids_are_rejected() {
+    let kvn = fs::read_to_string(format!("{}/iss.kvn", OMM_DIR)).expect("Failed to read the OMM");
+    let with_id = |id: &str| {
+        let contents = kvn.replace("OBJECT_ID = 1998-067A", &format!("OBJECT_ID = {}", id));
+
+        Omm::parse_kvn(&contents).expect("Failed to parse the OMM").remove(0).to_tle()
+    };
+
+    for id in ["1998-06é", "1998-067", "98-067A", "1998+067A", "1998-+67A", "199A-067A", "1998-067ABCD"] {
+        assert_eq!(with_id(id).err(), Some(OmmError::InvalidField("OBJECT_ID")), "{}", id);
+    }
+
+    let tle = with_id("1998-067A").expect("Failed to convert the OMM");
+    assert_eq!((tle.get_launch_year(), tle.get_launch_number(), tle.get_launch_piece()), (1998, 67, "A"));
+
+    // Without a designator it is left blank
+    let tle = with_id("").expect("Failed to convert the OMM");
+    assert_eq!((tle.get_launch_year(), tle.get_launch_number(), tle.get_launch_piece()), (0, 0, ""));
+}
@@ -0,0 +1,80 @@
+use sequisat::backend::satellite::Satellite;
+use sequisat::backend::tle::TLE;
+
+use chrono::{DateTime, TimeDelta, TimeZone, Utc};
+
+const ISS: &str = "\
+ISS (ZARYA)
+1 25544U 98067A   24354.78715486  .00013667  00000-0  24028-3 0  9996
+2 25544  51.6389 115.8117 0007806 357.7173 156.9527 15.50767798487337";
+
+fn iss() -> Satellite {
+    Satellite::new(TLE::parse(ISS).expect("Failed to parse the TLE"))
+}
+
+#[test]
+fn instants_are_minutes_since_epoch() {
+    let sat = iss();
+    let epoch = sat.get_tle().get_epoch();
+
+    assert_eq!(sat.minutes_since_epoch(epoch), 0.0);
+    assert_eq!(sat.minutes_since_epoch(epoch + TimeDelta::minutes(90)), 90.0);
+    assert_eq!(sat.minutes_since_epoch(epoch - TimeDelta::microseconds(60)), -1.0e-6);
+}
+
+#[test]
+fn propagation_at_an_instant_matches_the_minutes_since_epoch() {
+    let mut sat = iss();
+    let time = Utc.with_ymd_and_hms(2024, 12, 20, 6, 0, 0).unwrap();
+    let minutes = sat.minutes_since_epoch(time);
+
+    let at_instant = sat.propagate_at(time).expect("Failed to propagate");
+    let at_minutes = sat.propagate_minutes(minutes).expect("Failed to propagate");
+
+    assert_eq!(at_instant.get_position().get_x(), at_minutes.get_position().get_x());
+    assert_eq!(at_instant.get_position().get_y(), at_minutes.get_position().get_y());
+    assert_eq!(at_instant.get_position().get_z(), at_minutes.get_position().get_z());
+
+    // Low earth orbit
+    let radius = at_instant.get_position().dot(at_instant.get_position()).sqrt();
+    assert!(radius > 6700.0 && radius < 6850.0, "radius {}", radius);
+}
+
+#[test]
+fn updates_at_a_given_instant_are_reproducible() {
+    let mut sat = iss();
+    let time = Utc.with_ymd_and_hms(2024, 12, 21, 12, 30, 0).unwrap();
+
+    sat.update_position_at(time).expect("Failed to propagate");
+    let first = sat.get_geodetic_position();
+
+    sat.update_position_at(time + TimeDelta::hours(1)).expect("Failed to propagate");
+    sat.update_position_at(time).expect("Failed to propagate");
+    let second = sat.get_geodetic_position();
+
+    assert_eq!(first, second);
+}
+
+#[test]
+fn earth_fixed_position_is_a_rotation_of_teme() {
+    let mut sat = iss();
+    let time = Utc.with_ymd_and_hms(2024, 12, 20, 6, 0, 0).unwrap();
+
+    sat.update_position_at(time).expect("Failed to propagate");
+
+    let teme = sat.get_eci_position();
+    let ecef = sat.get_ecef_position();
+
+    // Without polar motion data only the earth rotation is applied, around the z axis
+    assert!((teme.dot(teme).sqrt() - ecef.dot(ecef).sqrt()).abs() < 1.0e-9);
+    assert_eq!(teme.get_z(), ecef.get_z());
+}
+
+#[test]
+fn ground_track_near_the_last_representable_date() {
+    let mut sat = iss();
+
+    // Only the minutes before the last date chrono can represent are in the track
+    sat.get_trajectory_at(DateTime::<Utc>::MAX_UTC - TimeDelta::minutes(10));
+    assert!(sat.get_points().len() <= 70);
+}
@@ -0,0 +1,69 @@
+use sequisat::backend::time::{self, J2000, JulianDate};
+
+use chrono::{TimeDelta, TimeZone, Utc};
+
+// Hours, minutes and seconds of an angle in rad
+fn to_hms(angle: f64) -> (i64, i64, f64) {
+    let hours = angle.to_degrees() / 15.0;
+    let minutes = (hours - hours.floor()) * 60.0;
+
+    (hours as i64, minutes as i64, (minutes - minutes.floor()) * 60.0)
+}
+
+#[test]
+fn julian_dates_of_known_instants() {
+    let j2000 = JulianDate::from_datetime(Utc.with_ymd_and_hms(2000, 1, 1, 12, 0, 0).unwrap());
+    assert_eq!(j2000.value(), J2000);
+    assert_eq!(j2000.mjd(), 51544.5);
+
+    // Launch of Sputnik 1, 1957 October 4.81 (Meeus, example 7.a)
+    let sputnik = JulianDate::from_datetime(Utc.with_ymd_and_hms(1957, 10, 4, 19, 26, 24).unwrap());
+    assert!((sputnik.value() - 2436116.31).abs() < 1.0e-9);
+
+    // Day of the year as in the TLE epochs
+    let epoch = JulianDate::from_day_of_year(2024, 354.78715486).expect("Year out of range");
+    assert_eq!(epoch.get_day(), 2460663.5);
+
+    // Years chrono can't represent have no date instead of a wrong one
+    assert_eq!(JulianDate::from_day_of_year(i32::MAX, 1.0), None);
+    assert_eq!(JulianDate::from_day_of_year(-300000, 1.0), None);
+    assert_eq!(JulianDate::from_mjd(60663.78715486).get_day(), 2460663.5);
+}
+
+#[test]
+fn julian_dates_keep_the_microseconds() {
+    let instant = Utc.with_ymd_and_hms(2024, 12, 19, 18, 53, 30).unwrap() + TimeDelta::microseconds(179904);
+    let julian_date = JulianDate::from_datetime(instant);
+
+    assert_eq!(julian_date.to_datetime(), Some(instant));
+    assert_eq!(julian_date.add_days(1.0).to_datetime(), Some(instant + TimeDelta::days(1)));
+}
+
+#[test]
+fn greenwich_mean_sidereal_time_iau82() {
+    // 1987 April 10 at 0h and 19h 21m UT (Meeus, examples 12.a and 12.b)
+    let (h, m, s) = to_hms(time::gmst_iau82(&JulianDate::new(2446895.5, 0.0)));
+    assert_eq!((h, m), (13, 10));
+    assert!((s - 46.3668).abs() < 1.0e-4, "{}", s);
+
+    let (h, m, s) = to_hms(time::gmst_iau82(&JulianDate::new(2446895.5, (19.0 + 21.0 / 60.0) / 24.0)));
+    assert_eq!((h, m), (8, 34));
+    assert!((s - 57.0896).abs() < 1.0e-4, "{}", s);
+
+    // SOFA test values (t_sofa_c, iauGmst82)
+    assert!((time::gmst_iau82(&JulianDate::new(2400000.5, 53736.0)) - 1.754174981860675).abs() < 1.0e-11);
+}
+
+#[test]
+fn earth_rotation_angle_and_gmst_iau2006() {
+    // At J2000.0 UT1 the angle is the constant of its definition, 2π × 0.7790572732640 (IERS Conventions 2010, eq. 5.15)
+    let era = time::earth_rotation_angle(&JulianDate::new(J2000, 0.0));
+    assert!((era - 2.0 * std::f64::consts::PI * 0.7790572732640).abs() < 1.0e-12, "{}", era);
+
+    // SOFA test values (t_sofa_c, iauEra00 and iauGmst06). The Astronomical Almanac tabulates the same IAU 2006
+    // expressions to 0.0001 s, these check them to 1e-12 rad
+    assert!((time::earth_rotation_angle(&JulianDate::new(2400000.5, 54388.0)) - 0.4022837240028158).abs() < 1.0e-12);
+
+    let date = JulianDate::new(2400000.5, 53736.0);
+    assert!((time::gmst_iau2006(&date, &date) - 1.754174971870091).abs() < 1.0e-12);
+}
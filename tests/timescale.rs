@@ -0,0 +1,89 @@
+use sequisat::backend::time::JulianDate;
+use sequisat::backend::timescale::{EopTable, LeapSeconds, TimeDataError, TimeScale, TimeScales};
+
+use chrono::{TimeZone, Utc};
+
+// The EOP files in tests/data/eop hold made up values in the layout of the real files, around the 2017 leap second
+
+fn seconds_between(a: &JulianDate, b: &JulianDate) -> f64 {
+    ((a.get_day() - b.get_day()) + (a.get_fraction() - b.get_fraction())) * 86400.0
+}
+
+#[test]
+fn bundled_leap_seconds() {
+    let leap_seconds = LeapSeconds::bundled();
+    let before = JulianDate::from_datetime(Utc.with_ymd_and_hms(2016, 12, 31, 23, 59, 59).unwrap());
+    let after = JulianDate::from_datetime(Utc.with_ymd_and_hms(2017, 1, 1, 0, 0, 0).unwrap());
+
+    assert_eq!(leap_seconds.tai_minus_utc(&before), 36.0);
+    assert_eq!(leap_seconds.tai_minus_utc(&after), 37.0);
+    assert_eq!(leap_seconds.tai_minus_utc(&JulianDate::from_mjd(41317.0)), 10.0);
+}
+
+#[test]
+fn leap_second_files() {
+    let leap_second_dat = "\
+#  File expires on 28 June 2025
+#    MJD        Date        TAI-UTC (s)
+#           day month year
+    41317.0    1  1 1972       10
+    57754.0    1  1 2017       37
+";
+    let leap_seconds_list = "\
+#$	 3676924800
+2272060800	10	# 1 Jan 1972
+3692217600	37	# 1 Jan 2017
+";
+    let date = JulianDate::from_mjd(57800.0);
+
+    assert_eq!(LeapSeconds::parse(leap_second_dat).expect("Failed to parse Leap_Second.dat").tai_minus_utc(&date), 37.0);
+    assert_eq!(LeapSeconds::parse(leap_seconds_list).expect("Failed to parse leap-seconds.list").tai_minus_utc(&date), 37.0);
+    assert_eq!(LeapSeconds::parse(leap_seconds_list).unwrap().get_entries()[0].0, 41317.0);
+
+    assert!(matches!(LeapSeconds::parse("41317.0 1 1 1972"), Err(TimeDataError::LeapSecond { line: 1 })));
+    assert!(matches!(LeapSeconds::parse("# only comments"), Err(TimeDataError::Empty)));
+}
+
+#[test]
+fn offsets_between_time_scales() {
+    let scales = TimeScales::default();
+    let time = Utc.with_ymd_and_hms(2024, 12, 19, 18, 0, 0).unwrap();
+    let utc = JulianDate::from_datetime(time);
+
+    assert_eq!(scales.offset_from_utc(TimeScale::TAI, &utc), 37.0);
+    assert_eq!(scales.offset_from_utc(TimeScale::GPS, &utc), 18.0);
+    assert!((seconds_between(&scales.tt(time), &utc) - 69.184).abs() < 1.0e-6);
+
+    // Without EOP data UT1 is taken as UTC
+    assert_eq!(scales.ut1(time), utc);
+}
+
+#[test]
+fn earth_orientation_files() {
+    let finals = EopTable::new("tests/data/eop/finals2000A.data").expect("Failed to read finals2000A");
+    let celestrak = EopTable::new("tests/data/eop/EOP-All.txt").expect("Failed to read the CelesTrak EOP file");
+
+    // The last day of finals2000A has no UT1-UTC yet
+    assert_eq!(finals.get_entries().len(), 4);
+    assert_eq!(finals.get_entries(), celestrak.get_entries());
+
+    let entry = finals.get(&JulianDate::from_mjd(57752.5)).expect("Inside the table");
+    assert!((entry.ut1_minus_utc - -0.395).abs() < 1.0e-9);
+    assert!((entry.x_pole.to_degrees() * 3600.0 - 0.0305).abs() < 1.0e-9);
+
+    // The leap second jump is not interpolated
+    let entry = finals.get(&JulianDate::from_mjd(57753.5)).expect("Inside the table");
+    assert!((entry.ut1_minus_utc - -0.405).abs() < 1.0e-9);
+
+    assert_eq!(finals.get(&JulianDate::from_mjd(57700.0)), None);
+    assert_eq!(finals.get(&JulianDate::from_mjd(57760.0)), None);
+}
+
+#[test]
+fn ut1_from_earth_orientation() {
+    let eop = EopTable::new("tests/data/eop/finals2000A.data").expect("Failed to read finals2000A");
+    let scales = TimeScales::new(LeapSeconds::bundled(), Some(eop));
+    let time = Utc.with_ymd_and_hms(2017, 1, 1, 0, 0, 0).unwrap();
+
+    assert!((seconds_between(&scales.ut1(time), &JulianDate::from_datetime(time)) - 0.59).abs() < 1.0e-6);
+}
@@ -0,0 +1,199 @@
+use sequisat::backend::catalog::Catalog;
+use sequisat::backend::tle::{Field, TLE, TleError, ValidationError, ValidationMode};
+
+use std::io::{self, BufReader, Cursor, Read};
+
+const ISS_LINE_1: &str = "1 25544U 98067A   24354.78715486  .00013667  00000-0  24028-3 0  9996";
+const ISS_LINE_2: &str = "2 25544  51.6389 115.8117 0007806 357.7173 156.9527 15.50767798487337";
+
+#[test]
+fn two_line_element_set_has_no_name() {
+    let tle = TLE::parse(&format!("{}\n{}", ISS_LINE_1, ISS_LINE_2)).expect("Failed to parse the TLE");
+
+    assert_eq!(tle.get_name(), None);
+    assert_eq!(tle.get_catalog_number(), 25544);
+}
+
+#[test]
+fn three_line_element_set_keeps_the_name() {
+    let tle = TLE::parse(&format!("ISS (ZARYA)\n{}\n{}\n", ISS_LINE_1, ISS_LINE_2)).expect("Failed to parse the TLE");
+
+    assert_eq!(tle.get_name(), Some("ISS (ZARYA)"));
+    assert_eq!(tle.get_catalog_number(), 25544);
+}
+
+#[test]
+fn space_track_name_prefix_is_removed() {
+    let tle = TLE::parse(&format!("0 ISS (ZARYA)\n{}\n{}", ISS_LINE_1, ISS_LINE_2)).expect("Failed to parse the TLE");
+
+    assert_eq!(tle.get_name(), Some("ISS (ZARYA)"));
+}
+
+#[test]
+fn valid_element_set_has_no_format_errors() {
+    assert!(TLE::validate(ISS_LINE_1, ISS_LINE_2).is_empty());
+}
+
+#[test]
+fn each_format_error_is_reported() {
+    // Wrong checksum on line 1
+    let corrupted = ISS_LINE_1.replace("9996", "9995");
+    assert_eq!(TLE::validate(&corrupted, ISS_LINE_2), vec![ValidationError::Checksum { line: 1, expected: 6 }]);
+
+    // Truncated line 2
+    assert_eq!(TLE::validate(ISS_LINE_1, &ISS_LINE_2[..68]), vec![ValidationError::LineLength { line: 2, length: 68 }]);
+
+    // Lines swapped
+    let errors = TLE::validate(ISS_LINE_2, ISS_LINE_1);
+    assert!(errors.contains(&ValidationError::LineNumber { line: 1 }));
+    assert!(errors.contains(&ValidationError::LineNumber { line: 2 }));
+
+    // Line 2 of another satellite
+    let other = "2 33591  99.0103 124.6938 0012767 286.7911  73.1860 14.13298055827724";
+    assert_eq!(TLE::validate(ISS_LINE_1, other), vec![ValidationError::CatalogNumberMismatch]);
+}
+
+#[test]
+fn strict_mode_rejects_corrupted_element_sets() {
+    let corrupted = ISS_LINE_1.replace("9996", "9995");
+
+    assert_eq!(
+        TLE::parse(&format!("{}\n{}", corrupted, ISS_LINE_2)).err(),
+        Some(TleError::Validation(ValidationError::Checksum { line: 1, expected: 6 }))
+    );
+}
+
+#[test]
+fn lenient_mode_keeps_the_errors_as_warnings() {
+    let corrupted = ISS_LINE_1.replace("9996", "9995");
+    let tle = TLE::parse_with_mode(&format!("{}\n{}", corrupted, ISS_LINE_2), ValidationMode::Lenient)
+        .expect("Failed to parse the TLE");
+
+    assert_eq!(tle.get_catalog_number(), 25544);
+    assert_eq!(tle.get_warnings(), &vec![ValidationError::Checksum { line: 1, expected: 6 }]);
+}
+
+#[test]
+fn fields_are_read_from_their_columns() {
+    // Negative second derivative touching B* and a three letter launch piece
+    let line_1 = "1 48275U 21035ABC 21115.24063657  .00010372 -11606-4  12082-3 0  9998";
+    let line_2 = "2 48275  41.4714 210.1093 0002208 274.3498 215.3463 15.61178962123459";
+    let tle = TLE::parse(&format!("{}\n{}", line_1, line_2)).expect("Failed to parse the TLE");
+
+    assert_eq!(tle.get_launch_year(), 2021);
+    assert_eq!(tle.get_launch_number(), 35);
+    assert_eq!(tle.get_launch_piece(), "ABC");
+    assert_eq!(tle.get_ballistic_coefficient(), 0.00010372);
+    assert_eq!(tle.get_mean_motion_ddot(), -0.11606e-4);
+    assert_eq!(tle.get_drag_term(), 0.12082e-3);
+    assert_eq!(tle.get_element_set_number(), 999);
+    assert_eq!(tle.get_eccetricity(), 0.0002208);
+    assert_eq!(tle.get_revolution_number(), 12345);
+    assert_eq!(tle.get_mean_motion(), 15.61178962 * 2.0 * std::f64::consts::PI / 1440.0);
+}
+
+#[test]
+fn alpha_5_catalog_numbers() {
+    let cases = [("00005", 5), ("99999", 99999), ("A0000", 100000), ("A0001", 100001), ("H9999", 179999), ("J0000", 180000), ("P0000", 230000), ("Z9999", 339999)];
+
+    for (field, number) in cases {
+        assert_eq!(TLE::decode_catalog_number(field), Ok(number), "decoding {}", field);
+        assert_eq!(TLE::encode_catalog_number(number).as_deref(), Ok(field), "encoding {}", number);
+    }
+
+    assert!(TLE::decode_catalog_number("I0000").is_err());
+    assert!(TLE::decode_catalog_number("O0000").is_err());
+
+    // Signs, inner dashes, spaces and fields of the wrong length are not catalog numbers
+    for field in ["+1234", "1-234", "-1234", "A+123", "A-123", "A123", " 1234", "1234 ", "123456", "é1234", "Aé123", "a0000"] {
+        assert!(TLE::decode_catalog_number(field).is_err(), "decoding {}", field);
+    }
+    assert!(TLE::encode_catalog_number(340000).is_err());
+}
+
+#[test]
+fn alpha_5_element_set_is_parsed() {
+    let line_1 = "1 A0001U 21035A   21115.24063657  .00010372  00000-0  12082-3 0  9994";
+    let line_2 = "2 A0001  41.4714 210.1093 0002208 274.3498 215.3463 15.61178962123454";
+    let tle = TLE::parse(&format!("{}\n{}", line_1, line_2)).expect("Failed to parse the TLE");
+
+    assert_eq!(tle.get_catalog_number(), 100001);
+}
+
+#[test]
+fn element_set_from_its_lines() {
+    let tle = TLE::from_lines(Some("ISS (ZARYA)"), ISS_LINE_1, ISS_LINE_2, ValidationMode::Strict).expect("Failed to parse the TLE");
+
+    assert_eq!(tle.get_name(), Some("ISS (ZARYA)"));
+    assert_eq!(tle.get_catalog_number(), 25544);
+}
+
+#[test]
+fn element_sets_are_read_one_at_a_time() {
+    let noaa_19 = "\
+NOAA 19
+1 33591U 09005A   25060.59715034  .00000391  00000-0  23254-3 0  9994
+2 33591  99.0103 124.6938 0012767 286.7911  73.1860 14.13298055827724
+";
+    let mut reader = Cursor::new(format!("{}\n{}\n\n{}\n\n", ISS_LINE_1, ISS_LINE_2, noaa_19));
+
+    let first = TLE::from_reader(&mut reader, ValidationMode::Strict).expect("Failed to read the first TLE");
+    let second = TLE::from_reader(&mut reader, ValidationMode::Strict).expect("Failed to read the second TLE");
+
+    assert_eq!(first.map(|tle| tle.get_catalog_number()), Some(25544));
+    assert_eq!(second.as_ref().and_then(|tle| tle.get_name()), Some("NOAA 19"));
+
+    // Only blank lines are left, the end of the reader is not an error
+    assert!(matches!(TLE::from_reader(&mut reader, ValidationMode::Strict), Ok(None)));
+    assert!(matches!(TLE::from_reader(&mut reader, ValidationMode::Strict), Ok(None)));
+}
+
+#[test]
+fn truncated_element_set_is_an_error() {
+    let mut reader = Cursor::new(format!("ISS (ZARYA)\n{}\n", ISS_LINE_1));
+
+    assert!(TLE::from_reader(&mut reader, ValidationMode::Strict).is_err());
+}
+
+// Reader that fails as a broken pipe would
+struct FailingReader;
+
+impl Read for FailingReader {
+    fn read(&mut self, _: &mut [u8]) -> io::Result<usize> {
+        Err(io::Error::new(io::ErrorKind::BrokenPipe, "broken pipe"))
+    }
+}
+
+#[test]
+fn io_failures_are_errors() {
+    let mut reader = BufReader::new(FailingReader);
+
+    assert!(TLE::from_reader(&mut reader, ValidationMode::Strict).is_err());
+    assert!(Catalog::from_reader(BufReader::new(FailingReader), ValidationMode::Strict).is_err());
+    assert!(TLE::new("this/file/does/not/exist.tle").is_err());
+}
+
+#[test]
+fn errors_point_to_the_failing_field() {
+    let corrupted = ISS_LINE_2.replace("0007806", "00O7806");
+    let error = TLE::parse_with_mode(&format!("{}\n{}", ISS_LINE_1, corrupted), ValidationMode::Lenient)
+        .err()
+        .expect("The eccentricity should not parse");
+
+    assert_eq!(error, TleError::InvalidField(Field::ECCENTRICITY));
+    assert_eq!(error.to_string(), "line 2 col 27-33: eccentricity");
+
+    assert_eq!(TLE::parse(ISS_LINE_1).err(), Some(TleError::MissingLine { line: 2 }));
+}
+
+#[test]
+fn epoch_is_kept_to_the_microsecond() {
+    let tle = TLE::parse(&format!("{}\n{}", ISS_LINE_1, ISS_LINE_2)).expect("Failed to parse the TLE");
+
+    // 0.78715486 days are 68010.179904 s
+    assert_eq!(tle.get_epoch().to_rfc3339_opts(chrono::SecondsFormat::Micros, true), "2024-12-19T18:53:30.179904Z");
+
+    let epoch = tle.get_epoch_julian_date();
+    assert_eq!(epoch.get_day(), 2460663.5);
+    assert!((epoch.get_fraction() - 0.78715486).abs() < 1.0e-12);
+}
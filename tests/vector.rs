@@ -0,0 +1,73 @@
+use sequisat::backend::matrix::Matrix3;
+use sequisat::backend::vector::PositionVector;
+
+use core::f64::consts::FRAC_PI_2;
+
+fn assert_close(vector: PositionVector, expected: [f64; 3]) {
+    let values = [vector.get_x(), vector.get_y(), vector.get_z()];
+
+    for (value, expected) in values.iter().zip(expected) {
+        assert!((value - expected).abs() < 1.0e-15, "{:?} instead of {:?}", vector, expected);
+    }
+}
+
+#[test]
+fn vector_arithmetic() {
+    let a = PositionVector::new(1.0, 2.0, 3.0);
+    let b = PositionVector::new(-4.0, 0.5, 2.0);
+
+    assert_eq!(a + b, PositionVector::new(-3.0, 2.5, 5.0));
+    assert_eq!(a - b, PositionVector::new(5.0, 1.5, 1.0));
+    assert_eq!(a * 2.0, PositionVector::new(2.0, 4.0, 6.0));
+    assert_eq!(-a, PositionVector::new(-1.0, -2.0, -3.0));
+
+    assert_eq!(PositionVector::new(3.0, 4.0, 12.0).norm(), 13.0);
+    assert_close(PositionVector::new(0.0, 3.0, 4.0).normalize(), [0.0, 0.6, 0.8]);
+}
+
+#[test]
+fn cross_product() {
+    let x = PositionVector::new(1.0, 0.0, 0.0);
+    let y = PositionVector::new(0.0, 1.0, 0.0);
+
+    assert_eq!(x.cross(&y), PositionVector::new(0.0, 0.0, 1.0));
+    assert_eq!(y.cross(&x), PositionVector::new(0.0, 0.0, -1.0));
+
+    // Perpendicular to both
+    let a = PositionVector::new(1.0, 2.0, 3.0);
+    let b = PositionVector::new(-4.0, 0.5, 2.0);
+    assert_eq!(a.cross(&b).dot(&a), 0.0);
+    assert_eq!(a.cross(&b).dot(&b), 0.0);
+}
+
+#[test]
+fn rotations_turn_the_axes() {
+    let x = PositionVector::new(1.0, 0.0, 0.0);
+    let y = PositionVector::new(0.0, 1.0, 0.0);
+    let z = PositionVector::new(0.0, 0.0, 1.0);
+
+    // Turning the axes 90 deg counterclockwise leaves the vectors 90 deg clockwise of them
+    assert_close(Matrix3::rotation_z(FRAC_PI_2) * x, [0.0, -1.0, 0.0]);
+    assert_close(Matrix3::rotation_x(FRAC_PI_2) * y, [0.0, 0.0, -1.0]);
+    assert_close(Matrix3::rotation_y(FRAC_PI_2) * z, [-1.0, 0.0, 0.0]);
+}
+
+#[test]
+fn composed_rotations() {
+    let v = PositionVector::new(1.0, 2.0, 3.0);
+
+    let first = Matrix3::rotation_x(0.3);
+    let second = Matrix3::rotation_z(-1.2);
+
+    // The right matrix is applied first
+    let composed = (second * first) * v;
+    let chained = second * (first * v);
+    assert_close(composed - chained, [0.0, 0.0, 0.0]);
+
+    // The transpose undoes the rotation and keeps the norm
+    let back = (second * first).transpose() * composed;
+    assert_close(back - v, [0.0, 0.0, 0.0]);
+    assert!((composed.norm() - v.norm()).abs() < 1.0e-15);
+
+    assert_eq!(Matrix3::identity() * v, v);
+}